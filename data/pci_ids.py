@@ -0,0 +1,41 @@
+#!/usr/bin/env python3
+# Regenerates pci.ids.deflate from an upstream pci.ids: python3 data/pci_ids.py /usr/share/hwdata/pci.ids
+# Every vendor name is kept (they're also used for board partners), device names only for vendors that make
+# display controllers. Subsystems and classes are dropped. The leading comment block is kept as is since it carries
+# the license (GPL-2.0+ or BSD-3-Clause) and the version we were generated from. Stored as raw deflate.
+import os
+import sys
+import zlib
+
+GPU_VENDORS = {
+    "1002", "10de", "8086",  # AMD, NVIDIA, Intel
+    "1af4", "1234", "1b36", "15ad", "80ee", "1414",  # virtio, QEMU, Red Hat, VMware, VirtualBox, Hyper-V
+    "1a03", "102b", "1013", "5333", "18ca",  # ASPEED, Matrox, Cirrus Logic, S3, XGI
+    "1d17", "1ed5", "0731",  # Zhaoxin, Moore Threads, Jingjia Micro
+}
+
+out = []
+vendor = None
+header = True
+for line in open(sys.argv[1], encoding="utf-8", errors="replace"):
+    line = line.rstrip("\n")
+    # Header ends at the first blank line
+    if header:
+        if line.startswith("#"):
+            out.append(line)
+            continue
+        header = False
+    if not line or line.startswith("#"):
+        continue
+    # Known device classes start here
+    if line.startswith("C "):
+        break
+    if not line.startswith("\t"):
+        vendor = line[:4]
+        out.append(line)
+    elif not line.startswith("\t\t") and vendor in GPU_VENDORS:
+        out.append(line)
+
+compressor = zlib.compressobj(9, zlib.DEFLATED, -15)
+data = compressor.compress(("\n".join(out) + "\n").encode()) + compressor.flush()
+open(os.path.join(os.path.dirname(__file__), "pci.ids.deflate"), "wb").write(data)
@@ -0,0 +1,49 @@
+use std::{fs, path::{Path, PathBuf}, time::UNIX_EPOCH};
+
+use crate::utils::xdg_dir;
+
+// Tiny on-disk cache for values that are slow to find but rarely change, e.g. DE versions.
+// Every entry is stamped with the mtime of the file it was derived from so an update invalidates it.
+// Format is one `key\tstamp\tvalue` entry per line.
+
+fn cache_file() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_CACHE_HOME", ".cache")?.join("yatfpbnws").join("cache"))
+}
+
+/// Modification time of `path` in seconds, used as the stamp for anything read from that file
+pub fn mtime_stamp(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs().to_string())
+}
+
+pub fn get_cached(key: &str, stamp: &str) -> Option<String> {
+    let contents = fs::read_to_string(cache_file()?).ok()?;
+    for line in contents.lines() {
+        let mut parts = line.splitn(3, '\t');
+        if parts.next() == Some(key) && parts.next() == Some(stamp) {
+            return parts.next().map(|v| v.to_string());
+        }
+    }
+    None
+}
+
+/// Stores a value, replacing any older entry under the same key. Failing to write is not an error, we just
+/// do the slow lookup again next time
+pub fn set_cached(key: &str, stamp: &str, value: &str) {
+    let Some(path) = cache_file() else {
+        return;
+    };
+
+    let mut lines: Vec<String> = fs::read_to_string(&path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| l.split('\t').next() != Some(key))
+        .map(|l| l.to_string())
+        .collect();
+    lines.push(format!("{}\t{}\t{}", key, stamp, value));
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(&path, lines.join("\n") + "\n");
+}
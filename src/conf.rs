@@ -0,0 +1,234 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use crate::{
+    units::Units,
+    utils::{ini_value, xdg_dir},
+};
+
+// Config is an ini file, `--conf` or `$XDG_CONFIG_HOME/yatfpbnws/config`. Missing files, keys and bad values
+// all fall back to the defaults so a partial config is fine. Example:
+//
+// [cpu]
+// frequency = max
+// governor = false
+// details = true
+// interval = 100
+// heatmap = sparkline
+//
+// [gpu]
+// software = true
+//
+// [displays]
+// randr = false
+//
+// [memory]
+// expanded = true
+// dimms = true
+// dmi_table = /path/to/dmidecode.bin
+//
+// [disks]
+// mounts = /, /home, /mnt/data
+//
+// [vulnerabilities]
+// show = compact
+//
+// [units]
+// bytes = si
+// precision = 2
+// temperature = fahrenheit
+
+/// Which frequency the CPU line shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuFrequency {
+    // Instantaneous mean, reads as the lowest P-state on an idle machine
+    Current,
+    // Advertised base clock, only known with intel_pstate
+    Base,
+    // Highest clock the driver allows, including boost
+    Max,
+    // Base and max side by side
+    All,
+}
+
+/// Per-core usage row under the CPU line, needs `--cpuusage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuHeatmap {
+    Off,
+    // A colored square per core
+    Cells,
+    // Bar height and color per core
+    Sparkline,
+}
+
+/// How much of the CPU vulnerability report to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VulnerabilityMode {
+    Off,
+    // `0 vulnerable, 9 mitigated`
+    Compact,
+    // One line per affected issue
+    Full,
+}
+
+/// Powers of 1024 (GiB) or 1000 (GB)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteUnits {
+    Iec,
+    Si,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+pub struct Config {
+    pub cpu_frequency: CpuFrequency,
+    // Append the scaling governor and energy preference to the CPU line
+    pub cpu_governor: bool,
+    // Microcode revision and stepping on the ISA line
+    pub cpu_details: bool,
+    // How long to sample for `--cpuusage`
+    pub cpu_interval: Duration,
+    pub cpu_heatmap: CpuHeatmap,
+    // List software renderers like llvmpipe as GPUs
+    pub gpu_software: bool,
+    // Ask the X server for the current modes, skipped automatically over ssh
+    pub displays_randr: bool,
+    // Cache/buffers/shared, huge pages, compressed swap and every swap device under RAM and SWAP
+    pub memory_expanded: bool,
+    // Installed modules from SMBIOS, the tables are only readable by root
+    pub memory_dimms: bool,
+    // A dumped SMBIOS table (`dmidecode --dump-bin`) to read the modules from instead of sysfs
+    pub memory_dmi_table: Option<String>,
+    // Mount points to list under Disks, None lists every real filesystem
+    pub disk_mounts: Option<Vec<String>>,
+    pub vulnerabilities: VulnerabilityMode,
+    pub units: Units,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cpu_frequency: CpuFrequency::Max,
+            cpu_governor: true,
+            cpu_details: false,
+            cpu_interval: sysinfo::MINIMUM_CPU_UPDATE_INTERVAL,
+            cpu_heatmap: CpuHeatmap::Off,
+            gpu_software: false,
+            displays_randr: true,
+            memory_expanded: false,
+            memory_dimms: false,
+            memory_dmi_table: None,
+            disk_mounts: Some(vec!["/".to_string(), "/home".to_string()]),
+            vulnerabilities: VulnerabilityMode::Off,
+            units: Units::default(),
+        }
+    }
+}
+
+fn config_file() -> Option<PathBuf> {
+    Some(xdg_dir("XDG_CONFIG_HOME", ".config")?.join("yatfpbnws").join("config"))
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn get_config(path: Option<&String>) -> Config {
+    let mut config = Config::default();
+    let Some(path) = path.map(PathBuf::from).or_else(config_file) else {
+        return config;
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return config;
+    };
+    let get = |section: &str, key: &str| ini_value(&contents, section, key);
+
+    if let Some(value) = get("cpu", "frequency") {
+        config.cpu_frequency = match value.to_lowercase().as_str() {
+            "current" => CpuFrequency::Current,
+            "base" => CpuFrequency::Base,
+            "max" => CpuFrequency::Max,
+            "all" => CpuFrequency::All,
+            _ => config.cpu_frequency,
+        };
+    }
+    if let Some(value) = get("cpu", "governor").and_then(|v| parse_bool(&v)) {
+        config.cpu_governor = value;
+    }
+    if let Some(value) = get("cpu", "details").and_then(|v| parse_bool(&v)) {
+        config.cpu_details = value;
+    }
+    // Milliseconds
+    if let Some(value) = get("cpu", "interval").and_then(|v| v.parse::<u64>().ok()) {
+        if value > 0 {
+            config.cpu_interval = Duration::from_millis(value);
+        }
+    }
+    if let Some(value) = get("cpu", "heatmap") {
+        config.cpu_heatmap = match value.to_lowercase().as_str() {
+            "off" | "false" => CpuHeatmap::Off,
+            "cells" | "true" => CpuHeatmap::Cells,
+            "sparkline" => CpuHeatmap::Sparkline,
+            _ => config.cpu_heatmap,
+        };
+    }
+    if let Some(value) = get("gpu", "software").and_then(|v| parse_bool(&v)) {
+        config.gpu_software = value;
+    }
+    if let Some(value) = get("displays", "randr").and_then(|v| parse_bool(&v)) {
+        config.displays_randr = value;
+    }
+    if let Some(value) = get("memory", "expanded").and_then(|v| parse_bool(&v)) {
+        config.memory_expanded = value;
+    }
+    if let Some(value) = get("memory", "dimms").and_then(|v| parse_bool(&v)) {
+        config.memory_dimms = value;
+    }
+    if let Some(value) = get("memory", "dmi_table") {
+        if !value.is_empty() {
+            config.memory_dmi_table = Some(value);
+        }
+    }
+    // `all` or a comma separated list of mount points
+    if let Some(value) = get("disks", "mounts") {
+        config.disk_mounts = if value.eq_ignore_ascii_case("all") {
+            None
+        } else {
+            Some(value.split(',').map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).collect())
+        };
+    }
+    if let Some(value) = get("vulnerabilities", "show") {
+        config.vulnerabilities = match value.to_lowercase().as_str() {
+            "off" | "false" => VulnerabilityMode::Off,
+            "compact" => VulnerabilityMode::Compact,
+            "full" | "true" => VulnerabilityMode::Full,
+            _ => config.vulnerabilities,
+        };
+    }
+    if let Some(value) = get("units", "bytes") {
+        config.units.bytes = match value.to_lowercase().as_str() {
+            "iec" | "binary" => ByteUnits::Iec,
+            "si" | "decimal" => ByteUnits::Si,
+            _ => config.units.bytes,
+        };
+    }
+    if let Some(value) = get("units", "precision").and_then(|v| v.parse::<usize>().ok()) {
+        config.units.precision = value.min(6);
+    }
+    if let Some(value) = get("units", "temperature") {
+        config.units.temperature = match value.to_lowercase().as_str() {
+            "c" | "celsius" => TemperatureUnit::Celsius,
+            "f" | "fahrenheit" => TemperatureUnit::Fahrenheit,
+            _ => config.units.temperature,
+        };
+    }
+
+    config
+}
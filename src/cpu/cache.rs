@@ -0,0 +1,178 @@
+// Cache hierarchy from sysfs. Every logical CPU lists every cache it can reach, so shared caches are
+// deduplicated by their cpu list before adding anything up
+use std::{collections::HashSet, fs};
+
+use super::topology::{cpu_path, parse_cpu_list};
+
+#[derive(Debug, Clone)]
+pub struct CacheInstance {
+    pub level: u8,
+    // `Data`, `Instruction` or `Unified`
+    pub kind: String,
+    pub size_kib: u64,
+    pub shared_cpus: Vec<usize>,
+    // Physical cores behind `shared_cpus`, SMT siblings share everything so only this says whether a cache
+    // is private or shared
+    pub shared_cores: usize,
+}
+
+impl CacheInstance {
+    fn label(&self) -> String {
+        match self.kind.as_str() {
+            "Data" => format!("L{}d", self.level),
+            "Instruction" => format!("L{}i", self.level),
+            _ => format!("L{}", self.level),
+        }
+    }
+}
+
+/// Parses sysfs cache sizes like `48K` or `32768K`
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let (number, multiplier) = match text.chars().last()? {
+        'K' => (&text[..text.len() - 1], 1),
+        'M' => (&text[..text.len() - 1], 1024),
+        'G' => (&text[..text.len() - 1], 1024 * 1024),
+        _ => (text, 1),
+    };
+    number.parse::<u64>().ok().map(|n| n * multiplier)
+}
+
+fn format_size(kib: u64) -> String {
+    if kib >= 1024 && kib % 1024 == 0 {
+        format!("{}M", kib / 1024)
+    } else {
+        format!("{}K", kib)
+    }
+}
+
+fn count_cores(cpus: &[usize]) -> usize {
+    let cores: HashSet<String> = cpus
+        .iter()
+        .map(|c| {
+            fs::read_to_string(cpu_path(*c).join("topology/core_cpus_list"))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| c.to_string())
+        })
+        .collect();
+    cores.len()
+}
+
+/// Every distinct cache reachable from the given CPUs
+pub fn get_caches(cpus: &[usize]) -> Vec<CacheInstance> {
+    let mut caches: Vec<CacheInstance> = vec![];
+
+    for cpu in cpus {
+        let Ok(entries) = fs::read_dir(cpu_path(*cpu).join("cache")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_name().to_string_lossy().starts_with("index") {
+                continue;
+            }
+            let read = |file: &str| fs::read_to_string(entry.path().join(file)).ok().map(|s| s.trim().to_string());
+
+            let (Some(level), Some(kind), Some(size)) = (read("level"), read("type"), read("size")) else {
+                continue;
+            };
+            let (Ok(level), Some(size_kib)) = (level.parse(), parse_size(&size)) else {
+                continue;
+            };
+            let shared_cpus = read("shared_cpu_list").map(|s| parse_cpu_list(&s)).unwrap_or_else(|| vec![*cpu]);
+
+            if caches.iter().any(|c| c.level == level && c.kind == kind && c.shared_cpus == shared_cpus) {
+                continue;
+            }
+            let shared_cores = count_cores(&shared_cpus);
+            caches.push(CacheInstance { level, kind, size_kib, shared_cpus, shared_cores });
+        }
+    }
+
+    caches.sort_by(|a, b| (a.level, &a.kind).cmp(&(b.level, &b.kind)));
+    caches
+}
+
+/// `L1d 384K (8x48K), L1i 256K (8x32K), L2 8M (8x1M), L3 128M (96M+32M, 8C each)`
+pub fn format_caches(caches: &[CacheInstance]) -> Option<String> {
+    let mut labels: Vec<String> = vec![];
+    for cache in caches {
+        if !labels.contains(&cache.label()) {
+            labels.push(cache.label());
+        }
+    }
+
+    let parts: Vec<String> = labels
+        .iter()
+        .map(|label| {
+            let group: Vec<&CacheInstance> = caches.iter().filter(|c| c.label() == *label).collect();
+            let total: u64 = group.iter().map(|c| c.size_kib).sum();
+
+            // X3D chips have one CCD with a much bigger L3 than the other
+            let mut sizes: Vec<u64> = group.iter().map(|c| c.size_kib).collect();
+            sizes.sort_by(|a, b| b.cmp(a));
+            sizes.dedup();
+            let mut details = if sizes.len() == 1 {
+                match group.len() {
+                    1 => vec![],
+                    n => vec![format!("{}x{}", n, format_size(sizes[0]))],
+                }
+            } else {
+                vec![group.iter().map(|c| format_size(c.size_kib)).collect::<Vec<_>>().join("+")]
+            };
+
+            // Hybrid chips mix private and clustered caches on the same level, those are left alone
+            let cores = group[0].shared_cores;
+            if cores > 1 && group.iter().all(|c| c.shared_cores == cores) {
+                details.push(format!("{}C each", cores));
+            }
+
+            if details.is_empty() {
+                format!("{} {}", label, format_size(total))
+            } else {
+                format!("{} {} ({})", label, format_size(total), details.join(", "))
+            }
+        })
+        .collect();
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(level: u8, kind: &str, size_kib: u64, shared_cpus: Vec<usize>) -> CacheInstance {
+        // Every test core has two threads
+        let shared_cores = shared_cpus.len() / 2;
+        CacheInstance { level, kind: kind.to_string(), size_kib, shared_cpus, shared_cores }
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("48K\n"), Some(48));
+        assert_eq!(parse_size("32768K"), Some(32768));
+        assert_eq!(parse_size("2M"), Some(2048));
+        assert_eq!(parse_size("bogus"), None);
+    }
+
+    #[test]
+    fn x3d_layout() {
+        // Two CCDs with 4 SMT cores each, the first one with V-Cache
+        let mut caches: Vec<CacheInstance> = vec![];
+        for core in 0..8 {
+            caches.push(cache(1, "Data", 32, vec![core * 2, core * 2 + 1]));
+            caches.push(cache(2, "Unified", 1024, vec![core * 2, core * 2 + 1]));
+        }
+        caches.push(cache(3, "Unified", 96 * 1024, (0..8).collect()));
+        caches.push(cache(3, "Unified", 32 * 1024, (8..16).collect()));
+
+        assert_eq!(
+            format_caches(&caches).unwrap(),
+            "L1d 256K (8x32K), L2 8M (8x1M), L3 128M (96M+32M, 4C each)"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// cpufreq limits and policy. The instantaneous frequency sysinfo reports sits at the lowest P-state on an idle
+// machine, the limits say a lot more about the chip
+use std::{fs, path::Path};
+
+use super::topology::cpu_path;
+use crate::{conf::CpuFrequency, units::Units};
+
+#[derive(Debug, Default)]
+pub struct CpuFreq {
+    // All in kHz like sysfs. P and E cores differ, so this is the lowest min and the highest max and base in the group
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub base: Option<u64>,
+    pub governors: Vec<String>,
+    // Energy performance preference, intel_pstate and amd-pstate in active mode only
+    pub epps: Vec<String>,
+    pub boost: Option<bool>,
+}
+
+fn read_khz(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn mhz(khz: u64) -> f64 {
+    khz as f64 / 1000.0
+}
+
+// The global switch if the driver has one. intel_pstate inverts it
+fn get_boost(cpu: usize) -> Option<bool> {
+    let read = |path: &Path| fs::read_to_string(path).ok().map(|s| s.trim() == "1");
+
+    read(Path::new("/sys/devices/system/cpu/cpufreq/boost"))
+        .or_else(|| read(Path::new("/sys/devices/system/cpu/intel_pstate/no_turbo")).map(|no_turbo| !no_turbo))
+        .or_else(|| read(&cpu_path(cpu).join("cpufreq/boost")))
+}
+
+/// cpufreq state of the given CPUs, None without a cpufreq driver (most VMs)
+pub fn get_cpufreq(cpus: &[usize]) -> Option<CpuFreq> {
+    let mut freq = CpuFreq::default();
+    let mut found = false;
+
+    for cpu in cpus {
+        let dir = cpu_path(*cpu).join("cpufreq");
+        if !dir.is_dir() {
+            continue;
+        }
+        found = true;
+
+        // Option's ordering puts None first, so `min` can't be used the way `max` is below
+        if let Some(min) = read_khz(&dir.join("cpuinfo_min_freq")) {
+            freq.min = Some(freq.min.map_or(min, |m| m.min(min)));
+        }
+        freq.max = freq.max.max(read_khz(&dir.join("cpuinfo_max_freq")));
+        freq.base = freq.base.max(read_khz(&dir.join("base_frequency")));
+        for (file, values) in [
+            ("scaling_governor", &mut freq.governors),
+            ("energy_performance_preference", &mut freq.epps),
+        ] {
+            if let Ok(value) = fs::read_to_string(dir.join(file)) {
+                let value = value.trim().to_string();
+                if !value.is_empty() && !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+    }
+
+    if !found {
+        return None;
+    }
+    freq.boost = get_boost(*cpus.first()?);
+    Some(freq)
+}
+
+impl CpuFreq {
+    /// `5.4GHz max (powersave/balance_performance)`, `current` is the sysinfo mean in MHz
+    pub fn describe(&self, mode: CpuFrequency, current: f64, show_governor: bool, units: &Units) -> String {
+        let mut out = match (mode, self.base, self.max) {
+            (CpuFrequency::Current, _, _) => units.frequency(current),
+            (CpuFrequency::Base, Some(base), _) => format!("{} base", units.frequency(mhz(base))),
+            (CpuFrequency::Base | CpuFrequency::Max, _, Some(max)) => format!("{} max", units.frequency(mhz(max))),
+            (CpuFrequency::All, base, Some(max)) => {
+                let range = match self.min {
+                    Some(min) => units.frequency_pair(mhz(min), mhz(max)),
+                    None => format!("{} max", units.frequency(mhz(max))),
+                };
+                match base {
+                    Some(base) => format!("{}, {} base", range, units.frequency(mhz(base))),
+                    None => range,
+                }
+            }
+            _ => units.frequency(current),
+        };
+
+        let mut policy: Vec<String> = vec![];
+        if show_governor && !self.governors.is_empty() {
+            let mut governor = self.governors.join("+");
+            if !self.epps.is_empty() {
+                governor += format!("/{}", self.epps.join("+")).as_str();
+            }
+            policy.push(governor);
+        }
+        if self.boost == Some(false) {
+            policy.push("boost off".to_string());
+        }
+        if !policy.is_empty() {
+            out += format!(" ({})", policy.join(", ")).as_str();
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_modes() {
+        let freq = CpuFreq {
+            min: Some(800_000),
+            max: Some(5_400_000),
+            base: Some(3_400_000),
+            governors: vec!["powersave".to_string()],
+            epps: vec!["balance_performance".to_string()],
+            boost: Some(true),
+        };
+        let units = Units::default();
+        assert_eq!(freq.describe(CpuFrequency::Max, 400.0, true, &units), "5.4GHz max (powersave/balance_performance)");
+        assert_eq!(freq.describe(CpuFrequency::All, 400.0, false, &units), "0.8-5.4GHz, 3.4GHz base");
+        assert_eq!(freq.describe(CpuFrequency::Current, 400.0, false, &units), "400MHz");
+
+        // acpi-cpufreq has no base clock, fall back to max
+        let acpi = CpuFreq { max: Some(3_000_000), boost: Some(false), ..Default::default() };
+        assert_eq!(acpi.describe(CpuFrequency::Base, 400.0, true, &units), "3.0GHz max (boost off)");
+    }
+}
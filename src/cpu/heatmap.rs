@@ -0,0 +1,49 @@
+// One glyph per logical CPU colored by its load, the mean±error on the CPU line hides a single pegged core
+use owo_colors::OwoColorize;
+
+use crate::conf::CpuHeatmap;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Sparkline glyph for a usage in percent
+pub fn spark(usage: f64) -> char {
+    let idx = (usage.clamp(0.0, 100.0) / 100.0 * (SPARKS.len() - 1) as f64).round() as usize;
+    SPARKS[idx]
+}
+
+fn colorize(glyph: char, usage: f64) -> String {
+    if usage < 33.0 {
+        format!("{}", glyph.green())
+    } else if usage < 66.0 {
+        format!("{}", glyph.yellow())
+    } else {
+        format!("{}", glyph.red())
+    }
+}
+
+/// `usages` are per logical CPU in kernel order, None when the heatmap is turned off
+pub fn format_heatmap(usages: &[f64], style: CpuHeatmap) -> Option<String> {
+    let glyphs: String = match style {
+        CpuHeatmap::Off => return None,
+        CpuHeatmap::Cells => usages.iter().map(|u| colorize('■', *u)).collect(),
+        CpuHeatmap::Sparkline => usages.iter().map(|u| colorize(spark(*u), *u)).collect(),
+    };
+    if usages.is_empty() {
+        None
+    } else {
+        Some(glyphs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparks() {
+        assert_eq!(spark(0.0), '▁');
+        assert_eq!(spark(50.0), '▅');
+        assert_eq!(spark(100.0), '█');
+        assert_eq!(spark(250.0), '█');
+    }
+}
@@ -0,0 +1,170 @@
+// Instruction set details from /proc/cpuinfo: the x86-64 psABI level a binary built for this machine can
+// target, plus extensions worth knowing about when picking build flags
+use std::fs;
+
+#[derive(Debug, Default)]
+pub struct CpuInfoBlock {
+    pub processor: usize,
+    // `flags` on x86, `Features` on ARM
+    pub flags: Vec<String>,
+    pub microcode: Option<String>,
+    // `stepping` on x86, `CPU revision` on ARM
+    pub stepping: Option<String>,
+}
+
+/// Splits /proc/cpuinfo into one block per logical CPU
+pub fn parse_cpuinfo(text: &str) -> Vec<CpuInfoBlock> {
+    let mut blocks: Vec<CpuInfoBlock> = vec![];
+
+    for section in text.split("\n\n") {
+        let mut block: Option<CpuInfoBlock> = None;
+        let mut flags: Vec<String> = vec![];
+        let mut microcode = None;
+        let mut stepping = None;
+
+        for line in section.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "processor" => {
+                    if let Ok(processor) = value.parse() {
+                        block = Some(CpuInfoBlock { processor, ..Default::default() });
+                    }
+                }
+                "flags" | "Features" => flags = value.split_whitespace().map(|f| f.to_string()).collect(),
+                "microcode" => microcode = Some(value.to_string()),
+                "stepping" | "CPU revision" => stepping = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        if let Some(mut block) = block {
+            block.flags = flags;
+            block.microcode = microcode;
+            block.stepping = stepping;
+            blocks.push(block);
+        }
+    }
+
+    blocks
+}
+
+// Straight from the psABI, with cpuinfo's names (`pni` is SSE3, `abm` brings LZCNT)
+const X86_64_LEVELS: [&[&str]; 4] = [
+    &["lm", "cmov", "cx8", "fpu", "fxsr", "mmx", "sse", "sse2"],
+    &["cx16", "lahf_lm", "popcnt", "pni", "sse4_1", "sse4_2", "ssse3"],
+    &["avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "abm", "movbe", "xsave"],
+    &["avx512f", "avx512bw", "avx512cd", "avx512dq", "avx512vl"],
+];
+
+// (flag, shown as)
+const NOTABLE_EXTENSIONS: [(&str, &str); 17] = [
+    // x86
+    ("avx512f", "AVX-512"),
+    ("avx512_fp16", "AVX-512 FP16"),
+    ("amx_tile", "AMX"),
+    ("avx_vnni", "AVX-VNNI"),
+    ("sha_ni", "SHA-NI"),
+    ("aes", "AES-NI"),
+    ("vaes", "VAES"),
+    ("gfni", "GFNI"),
+    // ARM
+    ("asimd", "NEON"),
+    ("sve", "SVE"),
+    ("sve2", "SVE2"),
+    ("sme", "SME"),
+    ("atomics", "LSE"),
+    ("sha2", "SHA2"),
+    ("sha3", "SHA3"),
+    ("i8mm", "I8MM"),
+    ("bf16", "BF16"),
+];
+
+/// Highest x86-64 microarchitecture level the flags fully cover, None on other architectures
+pub fn x86_64_level(flags: &[String]) -> Option<usize> {
+    let has = |flag: &&str| flags.iter().any(|f| f == flag);
+    let level = X86_64_LEVELS.iter().take_while(|level| level.iter().all(has)).count();
+    if level == 0 {
+        None
+    } else {
+        Some(level)
+    }
+}
+
+/// `x86-64-v3, AVX-VNNI, SHA-NI, AES-NI` with `microcode 0x2b000603, stepping 1` appended if `show_details`
+pub fn describe_isa(block: &CpuInfoBlock, show_details: bool) -> Option<String> {
+    let mut parts: Vec<String> = vec![];
+
+    let level = x86_64_level(&block.flags);
+    if let Some(level) = level {
+        parts.push(format!("x86-64-v{}", level));
+    }
+    for (flag, name) in NOTABLE_EXTENSIONS {
+        // ARM uses `aes` too, but AES-NI is an x86 name
+        let name = if flag == "aes" && level.is_none() {
+            "AES"
+        } else {
+            name
+        };
+        if block.flags.iter().any(|f| f == flag) {
+            parts.push(name.to_string());
+        }
+    }
+
+    if show_details {
+        if let Some(microcode) = &block.microcode {
+            parts.push(format!("microcode {}", microcode));
+        }
+        if let Some(stepping) = &block.stepping {
+            parts.push(format!("stepping {}", stepping));
+        }
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+/// The cpuinfo block for the first of the given CPUs, every CPU in a group shares the same ISA
+pub fn get_isa(cpus: &[usize], show_details: bool) -> Option<String> {
+    let text = fs::read_to_string("/proc/cpuinfo").ok()?;
+    let blocks = parse_cpuinfo(&text);
+    let block = blocks.iter().find(|b| cpus.contains(&b.processor)).or(blocks.first())?;
+    describe_isa(block, show_details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X86_CPUINFO: &str = "processor\t: 0
+vendor_id\t: GenuineIntel
+stepping\t: 1
+microcode\t: 0x2b000603
+flags\t\t: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 clflush mmx fxsr sse sse2 ss ht syscall nx lm pni pclmulqdq ssse3 fma cx16 sse4_1 sse4_2 movbe popcnt aes xsave avx f16c lahf_lm abm bmi1 avx2 bmi2 sha_ni avx_vnni
+
+processor\t: 1
+flags\t\t: fpu
+";
+
+    #[test]
+    fn x86_level() {
+        let blocks = parse_cpuinfo(X86_CPUINFO);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].processor, 1);
+        assert_eq!(
+            describe_isa(&blocks[0], true).unwrap(),
+            "x86-64-v3, AVX-VNNI, SHA-NI, AES-NI, microcode 0x2b000603, stepping 1"
+        );
+    }
+
+    #[test]
+    fn arm_features() {
+        let blocks = parse_cpuinfo("processor\t: 0\nFeatures\t: fp asimd aes sha2 atomics sve sve2\nCPU revision\t: 0\n");
+        assert_eq!(describe_isa(&blocks[0], false).unwrap(), "AES, NEON, SVE, SVE2, LSE, SHA2");
+    }
+}
@@ -0,0 +1,106 @@
+mod cache;
+mod freq;
+mod heatmap;
+mod isa;
+mod topology;
+
+use std::collections::HashMap;
+
+use average::MeanWithError;
+use regex::Regex;
+use sysinfo::System;
+
+use cache::{format_caches, get_caches};
+use freq::get_cpufreq;
+use heatmap::format_heatmap;
+use isa::get_isa;
+use topology::get_topology;
+
+use crate::{conf::Config, utils::format_tree};
+
+struct CpuCnt {
+    count: i64,
+    name: String,
+    ids: Vec<usize>,       // Logical CPU numbers, matching `/sys/devices/system/cpu/cpuN`
+    usages: Vec<f64>,      // Percent
+    frequencies: Vec<f64>, // MHz
+}
+
+/// One tree entry per CPU model with its details underneath, and how many models there are for the label
+pub fn get_cpus(sys: &mut System, show_usage: bool, config: &Config) -> (String, usize) {
+    let mut cpus: Vec<String> = vec![];
+    let mut cpu_counter: HashMap<String, CpuCnt> = HashMap::new();
+
+    // Refresh cpu usage
+    sys.refresh_cpu_all();
+    if show_usage {
+        // Defaults to sysinfo's 200ms but half that works consistently and accurately on my machine ¯\_(ツ)_/¯
+        std::thread::sleep(config.cpu_interval);
+        sys.refresh_cpu_all();
+    }
+
+    for (i, cpu) in sys.cpus().iter().enumerate() {
+        let entry = cpu_counter
+            .entry(cpu.brand().to_string())
+            .or_insert_with(|| CpuCnt {
+                count: 0,
+                name: cpu.brand().to_string(),
+                ids: vec![],
+                usages: vec![],
+                frequencies: vec![]
+            });
+        entry.count += 1;
+        // sysinfo names them `cpu0`, `cpu1`, ... in the same order as the kernel
+        entry.ids.push(cpu.name().trim_start_matches("cpu").parse().unwrap_or(i));
+        entry.usages.push(cpu.cpu_usage() as f64);
+        entry.frequencies.push(cpu.frequency() as f64);
+    }
+
+    for (_, cpu) in cpu_counter.iter() {
+        let cpu_unneeded_removal = Regex::new(r"(?i)(\{tm\}|\{r\}|CPU|Processor|Dual-Core|Quad-Core|Six-Core|Eight-Core|\d+-core)").unwrap();
+        let trimmedname = cpu_unneeded_removal.replace_all(cpu.name.as_str(), "").trim().to_string();
+
+        let freq: MeanWithError = cpu.frequencies.iter().collect();
+        let usage: MeanWithError = cpu.usages.iter().collect();
+        let topology = get_topology(&cpu.ids);
+        let count = match topology {
+            Some(topology) => topology.to_string(),
+            None => cpu.count.to_string(),
+        };
+
+        let frequency = match get_cpufreq(&cpu.ids) {
+            Some(cpufreq) => cpufreq.describe(config.cpu_frequency, freq.mean(), config.cpu_governor, &config.units),
+            None => config.units.frequency(freq.mean()),
+        };
+
+        let mut details: Vec<String> = vec![];
+        if show_usage {
+            if let Some(heatmap) = format_heatmap(&cpu.usages, config.cpu_heatmap) {
+                details.push(heatmap);
+            }
+        }
+        if let Some(isa) = get_isa(&cpu.ids, config.cpu_details) {
+            details.push(isa);
+        }
+        if let Some(caches) = format_caches(&get_caches(&cpu.ids)) {
+            details.push(caches);
+        }
+
+        let header = if show_usage {
+            format!("{} ({}) @ {} ({:.1}±{:.1}%)", trimmedname, count, frequency, usage.mean(), usage.error())
+        } else {
+            format!("{} ({}) @ {}", trimmedname, count, frequency)
+        };
+        if details.is_empty() {
+            cpus.push(header);
+        } else {
+            cpus.push(header + "\n" + format_tree(&details).as_str());
+        }
+    }
+
+    if cpus.is_empty() {
+        return ("None... found?".to_string(), 0);
+    } else {
+        return (format_tree(&cpus), cpus.len());
+    }
+}
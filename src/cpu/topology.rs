@@ -0,0 +1,175 @@
+// Sockets, cores and threads from sysfs. sysinfo only knows about logical CPUs, which makes a 16 core/32 thread
+// chip look the same as a 32 core one and hides the P/E split on hybrid chips
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreKind {
+    Performance,
+    Efficiency,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Topology {
+    pub sockets: usize,
+    pub cores: usize,
+    pub threads: usize,
+    // Only filled on hybrid chips, (P cores, E cores)
+    pub hybrid: Option<(usize, usize)>,
+}
+
+/// Parses kernel cpu lists like `0-7,16-23`
+pub fn parse_cpu_list(text: &str) -> Vec<usize> {
+    let mut cpus: Vec<usize> = vec![];
+    for part in text.trim().split(',') {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => {
+                if let Ok(cpu) = part.parse() {
+                    cpus.push(cpu);
+                }
+            }
+        }
+    }
+    cpus
+}
+
+pub fn cpu_path(cpu: usize) -> PathBuf {
+    Path::new("/sys/devices/system/cpu").join(format!("cpu{}", cpu))
+}
+
+// Intel hybrid chips register a separate PMU per core type, ARM big.LITTLE only has a relative capacity
+// where the biggest cores get the highest number
+fn get_core_kinds(cpus: &[usize]) -> Option<Vec<CoreKind>> {
+    if let Ok(atoms) = fs::read_to_string("/sys/devices/cpu_atom/cpus") {
+        let atoms = parse_cpu_list(&atoms);
+        return Some(
+            cpus.iter()
+                .map(|c| {
+                    if atoms.contains(c) {
+                        CoreKind::Efficiency
+                    } else {
+                        CoreKind::Performance
+                    }
+                })
+                .collect(),
+        );
+    }
+
+    let capacities: Vec<u32> = cpus
+        .iter()
+        .map(|c| fs::read_to_string(cpu_path(*c).join("cpu_capacity")).ok()?.trim().parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let max = *capacities.iter().max()?;
+    if capacities.iter().all(|c| *c == max) {
+        return None;
+    }
+    Some(
+        capacities
+            .iter()
+            .map(|c| {
+                if *c == max {
+                    CoreKind::Performance
+                } else {
+                    CoreKind::Efficiency
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Topology of the given logical CPUs, None when sysfs doesn't have it (containers, non-Linux)
+pub fn get_topology(cpus: &[usize]) -> Option<Topology> {
+    let mut sockets: HashSet<String> = HashSet::new();
+    // A core is identified by its package and the threads sharing it, core_id alone repeats across dies
+    let mut cores: Vec<(String, String)> = vec![];
+    let mut core_of_cpu: Vec<usize> = vec![];
+
+    for cpu in cpus {
+        let topology = cpu_path(*cpu).join("topology");
+        let package = fs::read_to_string(topology.join("physical_package_id")).ok()?.trim().to_string();
+        let siblings = fs::read_to_string(topology.join("core_cpus_list"))
+            .or_else(|_| fs::read_to_string(topology.join("thread_siblings_list")))
+            .ok()?
+            .trim()
+            .to_string();
+
+        sockets.insert(package.clone());
+        let key = (package, siblings);
+        let core = match cores.iter().position(|c| *c == key) {
+            Some(i) => i,
+            None => {
+                cores.push(key);
+                cores.len() - 1
+            }
+        };
+        core_of_cpu.push(core);
+    }
+
+    let hybrid = get_core_kinds(cpus).map(|kinds| {
+        let mut counted: HashSet<usize> = HashSet::new();
+        let (mut p, mut e) = (0, 0);
+        for (core, kind) in core_of_cpu.iter().zip(kinds) {
+            if counted.insert(*core) {
+                match kind {
+                    CoreKind::Performance => p += 1,
+                    CoreKind::Efficiency => e += 1,
+                }
+            }
+        }
+        (p, e)
+    });
+
+    Some(Topology {
+        sockets: sockets.len(),
+        cores: cores.len(),
+        threads: cpus.len(),
+        hybrid,
+    })
+}
+
+impl std::fmt::Display for Topology {
+    /// `8P+8E/24T`, `8C/16T`, `2S 64C/128T` or just `4C` without SMT
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.sockets > 1 {
+            write!(f, "{}S ", self.sockets)?;
+        }
+        match self.hybrid {
+            Some((p, e)) => write!(f, "{}P+{}E", p, e)?,
+            None => write!(f, "{}C", self.cores)?,
+        }
+        if self.threads != self.cores {
+            write!(f, "/{}T", self.threads)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_lists() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn topology_format() {
+        let hybrid = Topology { sockets: 1, cores: 16, threads: 24, hybrid: Some((8, 8)) };
+        assert_eq!(hybrid.to_string(), "8P+8E/24T");
+        let server = Topology { sockets: 2, cores: 64, threads: 128, hybrid: None };
+        assert_eq!(server.to_string(), "2S 64C/128T");
+        let no_smt = Topology { sockets: 1, cores: 4, threads: 4, hybrid: None };
+        assert_eq!(no_smt.to_string(), "4C");
+    }
+}
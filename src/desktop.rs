@@ -1,37 +1,171 @@
-use std::{env, process::Command};
+use std::{env, fs, path::PathBuf};
 use detect_desktop_environment::DesktopEnvironment;
 use regex::Regex;
+use which::which;
 
-pub fn get_de() -> String {
-    let de = DesktopEnvironment::detect();
-    let mut detext = "Unknown".to_string();
-    if de.is_some() {
-        match de.unwrap() {
-            DesktopEnvironment::Kde => {
-                let tmp = Command::new("plasmashell").arg("--version").output();
-                detext = if tmp.is_ok() {
-                    let version_pattern = Regex::new(r"\d+\.\d+\.\d+").ok().unwrap();
-                    format!("Kde {}", version_pattern.find(String::from_utf8(tmp.unwrap().stdout.to_vec()).unwrap().as_str()).map(|m| m.as_str()).unwrap())
-                } else {
-                    "Kde".to_string()
-                }
-            }
-            _ => {
-                detext = format!("{:?}", de.unwrap());
-            }
+use crate::{
+    cache::{get_cached, mtime_stamp, set_cached},
+    packages::get_package_version,
+    utils::get_cmd_version,
+};
+
+// Where to look for a DE's version, tried in order. On-disk metadata is preferred since spawning
+// a desktop's binary can be very slow (or have side effects), and whatever we find gets cached
+enum VersionSource {
+    // GNOME style `<platform>`, `<minor>` and `<micro>` tags
+    VersionXml(&'static str),
+    // CMake package config containing `set(PACKAGE_VERSION "x.y.z")`
+    CmakeConfig(&'static str),
+    // Package name in the pacman/dpkg database
+    Package(&'static str),
+    // Binary and argument that print a version
+    Command(&'static str, &'static str),
+}
+
+fn version_sources(de_name: &str) -> &'static [VersionSource] {
+    match de_name {
+        "Kde" => &[
+            VersionSource::Package("plasma-workspace"),
+            VersionSource::Command("plasmashell", "--version"),
+        ],
+        "Gnome" => &[
+            VersionSource::VersionXml("/usr/share/gnome/gnome-version.xml"),
+            VersionSource::Package("gnome-shell"),
+            VersionSource::Command("gnome-shell", "--version"),
+        ],
+        "Xfce" => &[
+            VersionSource::Package("xfce4-session"),
+            VersionSource::Command("xfce4-session", "--version"),
+        ],
+        "Cinnamon" => &[
+            VersionSource::Package("cinnamon"),
+            VersionSource::Command("cinnamon", "--version"),
+        ],
+        "Mate" => &[
+            VersionSource::VersionXml("/usr/share/mate-about/mate-version.xml"),
+            VersionSource::Package("mate-session-manager"),
+            VersionSource::Command("mate-session", "--version"),
+        ],
+        "Lxqt" => &[
+            VersionSource::CmakeConfig("/usr/share/cmake/lxqt/lxqt-config-version.cmake"),
+            VersionSource::Package("lxqt-session"),
+            VersionSource::Command("lxqt-session", "--version"),
+        ],
+        "Budgie" => &[
+            VersionSource::Package("budgie-desktop"),
+            VersionSource::Command("budgie-desktop", "--version"),
+        ],
+        _ => &[],
+    }
+}
+
+fn read_version_xml(path: &str) -> Option<String> {
+    let xml = fs::read_to_string(path).ok()?;
+    let mut parts: Vec<String> = vec![];
+    for tag in ["platform", "minor", "micro"] {
+        let pattern = Regex::new(format!(r"<{0}>\s*(\d+)\s*</{0}>", tag).as_str()).unwrap();
+        // GNOME 40+ leaves micro empty, stop at the first missing part
+        match pattern.captures(xml.as_str()) {
+            Some(c) => parts.push(c[1].to_string()),
+            None => break,
         }
     }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("."))
+    }
+}
+
+fn read_cmake_config(path: &str) -> Option<String> {
+    let cmake = fs::read_to_string(path).ok()?;
+    let pattern = Regex::new(r#"set\s*\(\s*PACKAGE_VERSION\s+"([\d.]+)"\s*\)"#).unwrap();
+    pattern.captures(cmake.as_str()).map(|c| c[1].to_string())
+}
+
+// The file whose mtime tells us if a cached answer for this source is stale
+fn source_stamp_path(src: &VersionSource) -> Option<PathBuf> {
+    match src {
+        VersionSource::VersionXml(path) | VersionSource::CmakeConfig(path) => Some(PathBuf::from(path)),
+        VersionSource::Package(_) => ["/var/lib/pacman/local", "/var/lib/dpkg/status"]
+            .iter()
+            .map(PathBuf::from)
+            .find(|p| p.exists()),
+        VersionSource::Command(cmd, _) => which(cmd).ok(),
+    }
+}
+
+fn read_source(src: &VersionSource) -> Option<String> {
+    match src {
+        VersionSource::VersionXml(path) => read_version_xml(path),
+        VersionSource::CmakeConfig(path) => read_cmake_config(path),
+        VersionSource::Package(name) => get_package_version(name),
+        VersionSource::Command(cmd, arg) => get_cmd_version(cmd, arg),
+    }
+}
+
+fn get_de_version(de_name: &str) -> Option<String> {
+    let key = format!("de-version:{}", de_name);
+
+    for src in version_sources(de_name) {
+        let Some(stamp_path) = source_stamp_path(src) else {
+            continue;
+        };
+        let Some(stamp) = mtime_stamp(&stamp_path) else {
+            continue;
+        };
+        let stamp = format!("{}:{}", stamp_path.display(), stamp);
+
+        if let Some(version) = get_cached(key.as_str(), stamp.as_str()) {
+            return Some(version);
+        }
+        if let Some(version) = read_source(src) {
+            set_cached(key.as_str(), stamp.as_str(), version.as_str());
+            return Some(version);
+        }
+    }
+
+    None
+}
+
+pub fn get_display_server() -> &'static str {
     let x11_display = env::var("DISPLAY");
     let wayland_display = env::var("WAYLAND_DISPLAY");
     if wayland_display.is_ok() && x11_display.is_ok() {
-        detext += " (Wayland + XWayland)";
+        "Wayland + XWayland"
     } else if wayland_display.is_ok() {
-        detext += " (Wayland)";
+        "Wayland"
     } else if x11_display.is_ok() {
-        detext += " (X11)";
+        "X11"
     } else {
-        detext += " (Unknown)";
+        "Unknown"
+    }
+}
+
+/// Name of the running DE as printed on the DE line, without version or display server.
+/// None when no DE was found, or when the "DE" is really a standalone compositor that the WM line covers
+pub fn get_de_name() -> Option<String> {
+    // Budgie sets `Budgie:GNOME` which detect_desktop_environment reports as plain GNOME
+    let is_budgie = env::var("XDG_CURRENT_DESKTOP").is_ok_and(|d| d.contains("Budgie"));
+    if is_budgie {
+        return Some("Budgie".to_string());
+    }
+
+    match DesktopEnvironment::detect()? {
+        DesktopEnvironment::Hyprland | DesktopEnvironment::Sway => None,
+        de => Some(format!("{:?}", de)),
+    }
+}
+
+pub fn get_de() -> Option<String> {
+    let de_name = get_de_name()?;
+
+    let mut detext = de_name.clone();
+    if let Some(version) = get_de_version(de_name.as_str()) {
+        detext += format!(" {}", version).as_str();
     }
+    detext += format!(" ({})", get_display_server()).as_str();
 
-    return detext;
-}
\ No newline at end of file
+    return Some(detext);
+}
@@ -0,0 +1,243 @@
+// Memory modules from SMBIOS type 17 (Memory Device) structures, read straight from the kernel's DMI export
+// instead of shelling out to dmidecode. Both are root-only, so normal users get a hint instead
+use std::{fs, io::ErrorKind};
+
+use crate::{units::Units, utils::format_tree};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MemoryDevice {
+    // Slot name like `DIMM_A1` or `ChannelA-DIMM0`
+    pub locator: String,
+    // 0 for an empty slot
+    pub size_mib: u64,
+    pub memory_type: Option<&'static str>,
+    // MT/s, rated is what the module supports and configured what it's running at
+    pub rated_speed: Option<u32>,
+    pub configured_speed: Option<u32>,
+    pub manufacturer: Option<String>,
+    pub part_number: Option<String>,
+}
+
+fn memory_type(value: u8) -> Option<&'static str> {
+    match value {
+        0x12 => Some("DDR"),
+        0x13 => Some("DDR2"),
+        0x18 => Some("DDR3"),
+        0x1A => Some("DDR4"),
+        0x1B => Some("LPDDR"),
+        0x1C => Some("LPDDR2"),
+        0x1D => Some("LPDDR3"),
+        0x1E => Some("LPDDR4"),
+        0x20 => Some("HBM"),
+        0x21 => Some("HBM2"),
+        0x22 => Some("DDR5"),
+        0x23 => Some("LPDDR5"),
+        0x24 => Some("HBM3"),
+        _ => None,
+    }
+}
+
+// Strings follow the formatted area, numbered from 1, each null terminated. Firmware loves padding them with
+// spaces or filling them with placeholders
+fn get_string(strings: &[&[u8]], idx: u8) -> Option<String> {
+    let raw = strings.get((idx as usize).checked_sub(1)?)?;
+    let text = String::from_utf8_lossy(raw).trim().to_string();
+    let placeholder = ["", "Unknown", "Not Specified", "NO DIMM", "Undefined", "0000", "00000000"];
+    if placeholder.iter().any(|p| p.eq_ignore_ascii_case(&text)) {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn word(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes([*data.get(offset)?, *data.get(offset + 1)?]))
+}
+
+fn dword(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+// Speeds of 0 are unknown, 0xFFFF means the real value is in the 32 bit extended field
+fn speed(data: &[u8], offset: usize, extended: usize) -> Option<u32> {
+    match word(data, offset)? {
+        0 => None,
+        0xFFFF => dword(data, extended).filter(|s| *s > 0),
+        speed => Some(speed as u32),
+    }
+}
+
+/// Parses one type 17 structure including its strings, None for any other type
+pub fn parse_memory_device(raw: &[u8]) -> Option<MemoryDevice> {
+    if *raw.first()? != 17 {
+        return None;
+    }
+    let length = *raw.get(1)? as usize;
+    let data = raw.get(..length)?;
+    let strings: Vec<&[u8]> = raw.get(length..)?.split(|b| *b == 0).take_while(|s| !s.is_empty()).collect();
+
+    // Bit 15 set means KiB instead of MiB, 0x7FFF means the size is in the extended field
+    let size_mib = match word(data, 0x0C)? {
+        0xFFFF => 0,
+        0x7FFF => dword(data, 0x1C).unwrap_or(0) as u64 & 0x7FFF_FFFF,
+        size if size & 0x8000 != 0 => (size & 0x7FFF) as u64 / 1024,
+        size => size as u64,
+    };
+
+    Some(MemoryDevice {
+        locator: get_string(&strings, *data.get(0x10)?).unwrap_or_else(|| "?".to_string()),
+        size_mib,
+        memory_type: data.get(0x12).and_then(|t| memory_type(*t)),
+        rated_speed: speed(data, 0x15, 0x54),
+        configured_speed: speed(data, 0x20, 0x58),
+        manufacturer: data.get(0x17).and_then(|i| get_string(&strings, *i)),
+        part_number: data.get(0x1A).and_then(|i| get_string(&strings, *i)),
+    })
+}
+
+/// Walks a whole SMBIOS table (/sys/firmware/dmi/tables/DMI or a `dmidecode --dump-bin` file)
+pub fn parse_table(mut data: &[u8]) -> Vec<MemoryDevice> {
+    // dmidecode dumps start with the entry point, the table follows at offset 32
+    if data.starts_with(b"_SM") {
+        data = data.get(32..).unwrap_or_default();
+    }
+
+    let mut devices: Vec<MemoryDevice> = vec![];
+    let mut offset = 0;
+    while offset + 4 <= data.len() {
+        let kind = data[offset];
+        let length = data[offset + 1] as usize;
+        if length < 4 {
+            break;
+        }
+        // The string set ends with a double null
+        let Some(end) = data
+            .get(offset + length..)
+            .and_then(|rest| rest.windows(2).position(|w| w == [0, 0]))
+            .map(|p| offset + length + p + 2)
+        else {
+            break;
+        };
+
+        if kind == 17 {
+            if let Some(device) = parse_memory_device(&data[offset..end]) {
+                devices.push(device);
+            }
+        }
+        // End of table
+        if kind == 127 {
+            break;
+        }
+        offset = end;
+    }
+    devices
+}
+
+// Err(true) when the structures are there but unreadable
+fn read_devices(table: Option<&String>) -> Result<Vec<MemoryDevice>, bool> {
+    if let Some(table) = table {
+        return fs::read(table).map(|data| parse_table(&data)).map_err(|e| e.kind() == ErrorKind::PermissionDenied);
+    }
+
+    let Ok(entries) = fs::read_dir("/sys/firmware/dmi/entries") else {
+        return Err(false);
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("17-"))
+        .map(|e| e.path())
+        .collect();
+    paths.sort_by_key(|p| {
+        p.file_name()
+            .and_then(|f| f.to_string_lossy().trim_start_matches("17-").parse::<u32>().ok())
+            .unwrap_or(0)
+    });
+
+    let mut devices: Vec<MemoryDevice> = vec![];
+    for path in paths {
+        match fs::read(path.join("raw")) {
+            Ok(raw) => devices.extend(parse_memory_device(&raw)),
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => return Err(true),
+            Err(_) => continue,
+        }
+    }
+    Ok(devices)
+}
+
+fn describe_device(device: &MemoryDevice, units: &Units) -> String {
+    let mut out = format!("{}: {}", device.locator, units.bytes(device.size_mib * 1024 * 1024));
+    if let Some(memory_type) = device.memory_type {
+        out += format!(" {}", memory_type).as_str();
+    }
+    match (device.configured_speed, device.rated_speed) {
+        (Some(configured), Some(rated)) if configured != rated => {
+            out += format!(" {}/{}MT/s", configured, rated).as_str()
+        }
+        (Some(speed), _) | (None, Some(speed)) => out += format!(" {}MT/s", speed).as_str(),
+        _ => {}
+    }
+
+    let maker: Vec<&str> = [&device.manufacturer, &device.part_number].iter().filter_map(|s| s.as_deref()).collect();
+    if !maker.is_empty() {
+        out += format!(" ({})", maker.join(" ")).as_str();
+    }
+    out
+}
+
+/// `2/4 slots` with one sub-line per populated slot, or a hint when the tables need root.
+/// `table` is an optional dumped SMBIOS table to read instead of sysfs
+pub fn get_dimms(table: Option<&String>, units: &Units) -> Option<String> {
+    let devices = match read_devices(table) {
+        Ok(devices) => devices,
+        Err(true) => return Some("Run as root to read the SMBIOS memory tables".to_string()),
+        Err(false) => return None,
+    };
+    if devices.is_empty() {
+        return None;
+    }
+
+    let populated: Vec<&MemoryDevice> = devices.iter().filter(|d| d.size_mib > 0).collect();
+    let items: Vec<String> = populated.iter().map(|d| describe_device(d, units)).collect();
+    Some(format!("{}/{} slots\n{}", populated.len(), devices.len(), format_tree(&items)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Type 17, SMBIOS 3.3 layout (0x5C bytes), 16GiB DDR5 rated 6000 running at 5600
+    fn sample_device() -> Vec<u8> {
+        let mut raw = vec![0u8; 0x5C];
+        raw[0] = 17;
+        raw[1] = 0x5C;
+        raw[0x0C..0x0E].copy_from_slice(&16384u16.to_le_bytes());
+        raw[0x10] = 1;
+        raw[0x12] = 0x22;
+        raw[0x15..0x17].copy_from_slice(&6000u16.to_le_bytes());
+        raw[0x17] = 2;
+        raw[0x1A] = 3;
+        raw[0x20..0x22].copy_from_slice(&5600u16.to_le_bytes());
+        raw.extend_from_slice(b"DIMM_A1\0Kingston\0KF560C36-16   \0\0");
+        raw
+    }
+
+    #[test]
+    fn memory_device() {
+        let device = parse_memory_device(&sample_device()).unwrap();
+        assert_eq!(
+            describe_device(&device, &Units::default()),
+            "DIMM_A1: 16.0GiB DDR5 5600/6000MT/s (Kingston KF560C36-16)"
+        );
+    }
+
+    #[test]
+    fn table() {
+        // A type 0 structure with no strings, the module, then the end of table marker
+        let mut data = vec![0u8, 4, 0, 0, 0, 0];
+        data.extend(sample_device());
+        data.extend([127, 4, 0, 0, 0, 0]);
+        let devices = parse_table(&data);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].size_mib, 16384);
+    }
+}
@@ -0,0 +1,145 @@
+// Mounted filesystems from sysinfo. sysinfo already drops proc/sysfs/cgroup and squashfs, what's left still has
+// plenty of noise on a desktop: tmpfs and friends, snap loop devices, and every btrfs subvolume as its own disk
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use sysinfo::Disks;
+
+use crate::{units::Units, utils::format_tree};
+
+const PSEUDO_FILESYSTEMS: [&str; 20] = [
+    "autofs", "binfmt_misc", "bpf", "configfs", "debugfs", "devpts", "devtmpfs", "efivarfs", "fusectl", "hugetlbfs",
+    "mqueue", "nsfs", "overlay", "ramfs", "securityfs", "tmpfs", "tracefs", "fuse.gvfsd-fuse", "fuse.portal",
+    "fuse.lxcfs",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub device: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub available: u64,
+}
+
+fn is_pseudo(mount: &Mount) -> bool {
+    PSEUDO_FILESYSTEMS.contains(&mount.file_system.as_str())
+        || ["/dev", "/sys", "/proc", "/run/user"].iter().any(|p| Path::new(&mount.mount_point).starts_with(p))
+        || mount.total == 0
+}
+
+fn is_snap(mount: &Mount) -> bool {
+    mount.device.starts_with("/dev/loop")
+        && (mount.mount_point.starts_with("/snap/") || mount.mount_point.starts_with("/var/lib/snapd/"))
+}
+
+/// Drops pseudo filesystems and snaps, then folds btrfs subvolumes of the same device into one entry with all of
+/// their mount points. `only` keeps entries with at least one of the listed mount points, None keeps everything
+pub fn filter_mounts(mounts: &[Mount], only: Option<&[String]>) -> Vec<(Vec<String>, Mount)> {
+    let mut mounts: Vec<&Mount> = mounts.iter().filter(|m| !is_pseudo(m) && !is_snap(m)).collect();
+    mounts.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+
+    let mut out: Vec<(Vec<String>, Mount)> = vec![];
+    for mount in mounts {
+        let subvolume = out
+            .iter_mut()
+            .find(|(_, m)| mount.file_system == "btrfs" && m.file_system == "btrfs" && m.device == mount.device);
+        match subvolume {
+            Some((mount_points, _)) => mount_points.push(mount.mount_point.clone()),
+            None => out.push((vec![mount.mount_point.clone()], mount.clone())),
+        }
+    }
+
+    if let Some(only) = only {
+        out.retain_mut(|(mount_points, _)| {
+            mount_points.retain(|p| only.contains(p));
+            !mount_points.is_empty()
+        });
+    }
+    out
+}
+
+// Yellow once a filesystem is getting full, red when it's about to run out
+fn colorize(percent: f64) -> String {
+    let text = format!("{:.1}%", percent);
+    if percent >= 90.0 {
+        format!("{}", text.red())
+    } else if percent >= 75.0 {
+        format!("{}", text.yellow())
+    } else {
+        text
+    }
+}
+
+/// `/ (ext4): 120.3/465.8GiB (25.8%)`, one line per filesystem. None when nothing is left after filtering
+pub fn get_disks(only: Option<&[String]>, units: &Units) -> Option<String> {
+    let disks = Disks::new_with_refreshed_list();
+    let mounts: Vec<Mount> = disks
+        .list()
+        .iter()
+        .map(|d| Mount {
+            device: d.name().to_string_lossy().to_string(),
+            mount_point: d.mount_point().to_string_lossy().to_string(),
+            file_system: d.file_system().to_string_lossy().to_string(),
+            total: d.total_space(),
+            available: d.available_space(),
+        })
+        .collect();
+
+    let items: Vec<String> = filter_mounts(&mounts, only)
+        .iter()
+        .map(|(mount_points, mount)| {
+            let used = mount.total.saturating_sub(mount.available);
+            format!(
+                "{} ({}): {} ({})",
+                mount_points.join(", "),
+                mount.file_system,
+                units.bytes_pair(used, mount.total),
+                colorize(used as f64 / mount.total as f64 * 100.0)
+            )
+        })
+        .collect();
+
+    if items.is_empty() {
+        None
+    } else {
+        Some(format_tree(&items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mount(device: &str, mount_point: &str, file_system: &str) -> Mount {
+        Mount {
+            device: device.to_string(),
+            mount_point: mount_point.to_string(),
+            file_system: file_system.to_string(),
+            total: 100,
+            available: 50,
+        }
+    }
+
+    #[test]
+    fn filters() {
+        let mounts = [
+            mount("/dev/nvme0n1p2", "/home", "btrfs"),
+            mount("/dev/nvme0n1p2", "/", "btrfs"),
+            mount("/dev/nvme0n1p1", "/boot/efi", "vfat"),
+            mount("tmpfs", "/tmp", "tmpfs"),
+            mount("/dev/loop3", "/snap/firefox/4336", "ext4"),
+            mount("/dev/sda1", "/mnt/data", "ext4"),
+            // Whole path components only, ostree systems mount their root at /sysroot
+            mount("/dev/vda3", "/sysroot", "xfs"),
+            mount("devpts", "/dev/pts", "ext4"),
+        ];
+
+        let all: Vec<Vec<String>> = filter_mounts(&mounts, None).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(all, vec![vec!["/", "/home"], vec!["/boot/efi"], vec!["/mnt/data"], vec!["/sysroot"]]);
+
+        let only = ["/".to_string(), "/mnt/data".to_string()];
+        let some: Vec<Vec<String>> = filter_mounts(&mounts, Some(&only)).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(some, vec![vec!["/"], vec!["/mnt/data"]]);
+    }
+}
@@ -0,0 +1,415 @@
+// EDID 1.x base block parser
+// https://en.wikipedia.org/wiki/Extended_Display_Identification_Data#Structure,_version_1.4
+
+use super::extensions::{parse_extensions, ExtensionInfo};
+
+const EDID_HEADER_PATTERN: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const EDID_BLOCK_SIZE: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdidParseErrorType {
+    EarlyEof,
+    InvalidHeader,
+    BadChecksum,
+    UnsupportedEdidVer,
+}
+
+#[derive(Debug, Clone)]
+pub struct EdidParseError {
+    pub err_type: EdidParseErrorType,
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for EdidParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.message {
+            Some(msg) => write!(f, "{:?}: {}", self.err_type, msg),
+            None => write!(f, "{:?}", self.err_type),
+        }
+    }
+}
+
+macro_rules! edidError {
+    ($t:expr) => {
+        Err(EdidParseError { err_type: $t, message: None })
+    };
+    ($t:expr, $msg:expr) => {
+        Err(EdidParseError { err_type: $t, message: Some($msg.to_string()) })
+    };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetailedTiming {
+    pub width: u32,
+    pub height: u32,
+    pub refresh: f32,
+    pub interlaced: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edid {
+    pub manufacturer_id: String,
+    pub product_code: u16,
+    pub serial_number: u32,
+    // Week 0 means unspecified, 0xFF means `year` is a model year rather than a manufacture date
+    pub manufacture_week: u8,
+    pub manufacture_year: u16,
+    pub width_cm: u8,
+    pub height_cm: u8,
+    pub monitor_name: Option<String>,
+    pub serial_string: Option<String>,
+    pub preferred_timing: Option<DetailedTiming>,
+    // HDR, VRR and color depth info from the base block and every extension block
+    pub extensions: ExtensionInfo,
+}
+
+// Most common PNP IDs, anything not in here gets shown as the raw 3 letter code
+// https://uefi.org/PNP_ID_List
+const PNP_VENDORS: &[(&str, &str)] = &[
+    ("AAC", "AcerView"),
+    ("ACI", "ASUS"),
+    ("ACR", "Acer"),
+    ("AOC", "AOC"),
+    ("APP", "Apple"),
+    ("AUO", "AU Optronics"),
+    ("AUS", "ASUS"),
+    ("BNQ", "BenQ"),
+    ("BOE", "BOE"),
+    ("CMN", "Innolux"),
+    ("CMO", "Chi Mei"),
+    ("DEL", "Dell"),
+    ("EIZ", "EIZO"),
+    ("ENC", "EIZO"),
+    ("GBT", "Gigabyte"),
+    ("GSM", "LG"),
+    ("HKC", "HKC"),
+    ("HPN", "HP"),
+    ("HSD", "HannStar"),
+    ("HWP", "HP"),
+    ("IVM", "Iiyama"),
+    ("IVO", "InfoVision"),
+    ("LEN", "Lenovo"),
+    ("LGD", "LG Display"),
+    ("MEI", "Panasonic"),
+    ("MSI", "MSI"),
+    ("NEC", "NEC"),
+    ("PHL", "Philips"),
+    ("QEM", "QEMU"),
+    ("RHT", "Red Hat"),
+    ("SAM", "Samsung"),
+    ("SDC", "Samsung Display"),
+    ("SHP", "Sharp"),
+    ("SNY", "Sony"),
+    ("TSB", "Toshiba"),
+    ("VSC", "ViewSonic"),
+    ("XMI", "Xiaomi"),
+];
+
+pub fn vendor_name(pnp_id: &str) -> Option<&'static str> {
+    PNP_VENDORS
+        .iter()
+        .find(|(id, _)| *id == pnp_id)
+        .map(|(_, name)| *name)
+}
+
+// Descriptor text is up to 13 bytes, terminated by a newline and padded with spaces
+fn parse_descriptor_text(data: &[u8]) -> Option<String> {
+    let text: String = data
+        .iter()
+        .take_while(|b| **b != 0x0A)
+        .map(|b| *b as char)
+        .collect();
+    let text = text.trim().to_string();
+
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Parses an 18 byte detailed timing descriptor, also used by the CTA-861 extension block
+pub fn parse_detailed_timing(data: &[u8]) -> Option<DetailedTiming> {
+    // Pixel clock in 10kHz units, 0 means this is a display descriptor instead
+    let pixel_clock = u16::from_le_bytes([data[0], data[1]]) as u64 * 10_000;
+    if pixel_clock == 0 {
+        return None;
+    }
+
+    let h_active = data[2] as u32 | ((data[4] as u32 & 0xF0) << 4);
+    let h_blank = data[3] as u32 | ((data[4] as u32 & 0x0F) << 8);
+    let v_active = data[5] as u32 | ((data[7] as u32 & 0xF0) << 4);
+    let v_blank = data[6] as u32 | ((data[7] as u32 & 0x0F) << 8);
+    let interlaced = data[17] & 0x80 != 0;
+
+    let total = (h_active + h_blank) as u64 * (v_active + v_blank) as u64;
+    if total == 0 {
+        return None;
+    }
+    // Interlaced timings describe a single field, so this is already the field rate
+    let refresh = pixel_clock as f32 / total as f32;
+
+    let height = if interlaced {
+        v_active * 2
+    } else {
+        v_active
+    };
+
+    Some(DetailedTiming {
+        width: h_active,
+        height,
+        refresh,
+        interlaced,
+    })
+}
+
+impl std::fmt::Display for DetailedTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scan = if self.interlaced {
+            "i"
+        } else {
+            ""
+        };
+        write!(f, "{}x{}{}@{}Hz", self.width, self.height, scan, self.refresh.round())
+    }
+}
+
+impl Edid {
+    pub fn parse(data: &[u8]) -> Result<Edid, EdidParseError> {
+        if data.len() < EDID_BLOCK_SIZE {
+            return edidError!(EdidParseErrorType::EarlyEof, format!("Base block is only {} bytes", data.len()));
+        }
+        let block = &data[..EDID_BLOCK_SIZE];
+
+        if block[0..8] != EDID_HEADER_PATTERN {
+            return edidError!(EdidParseErrorType::InvalidHeader);
+        }
+        if block.iter().fold(0u8, |acc, b| acc.wrapping_add(*b)) != 0 {
+            return edidError!(EdidParseErrorType::BadChecksum);
+        }
+        if block[18] != 1 {
+            return edidError!(EdidParseErrorType::UnsupportedEdidVer, format!("EDID {}.{}", block[18], block[19]));
+        }
+
+        // 3 5-bit letters, 1 = 'A'
+        let id = u16::from_be_bytes([block[8], block[9]]);
+        let manufacturer_id: String = [10, 5, 0]
+            .iter()
+            .map(|shift| (((id >> shift) & 0b11111) as u8 + 0x40) as char)
+            .collect();
+
+        let mut out = Edid {
+            manufacturer_id,
+            product_code: u16::from_le_bytes([block[10], block[11]]),
+            serial_number: u32::from_le_bytes([block[12], block[13], block[14], block[15]]),
+            manufacture_week: block[16],
+            manufacture_year: block[17] as u16 + 1990,
+            width_cm: block[21],
+            height_cm: block[22],
+            monitor_name: None,
+            serial_string: None,
+            preferred_timing: None,
+            extensions: parse_extensions(data),
+        };
+
+        // Digital input on 1.4 has the panel bit depth in bits 6-4, 1 = 6 bit up to 6 = 16 bit
+        if block[20] & 0x80 != 0 && block[19] >= 4 {
+            let depth = (block[20] >> 4) & 0x07;
+            if (1..=6).contains(&depth) {
+                out.extensions.add_bit_depth(4 + depth * 2);
+            }
+        }
+        // Continuous frequency support means the range limits descriptor is an actual VRR range
+        let continuous_freq = block[24] & 0x01 != 0;
+
+        // 4 18 byte descriptors, the first one is always the preferred timing on EDID 1.3+
+        for offset in [54, 72, 90, 108] {
+            let desc = &block[offset..offset + 18];
+            if desc[0] != 0 || desc[1] != 0 {
+                if out.preferred_timing.is_none() {
+                    out.preferred_timing = parse_detailed_timing(desc);
+                }
+                continue;
+            }
+            match desc[3] {
+                0xFC => out.monitor_name = parse_descriptor_text(&desc[5..]),
+                0xFF => out.serial_string = parse_descriptor_text(&desc[5..]),
+                0xFD if continuous_freq => {
+                    // Flag bits add 255 to the min/max vertical rates on 1.4
+                    let mut min = desc[5] as u16;
+                    let mut max = desc[6] as u16;
+                    if desc[4] & 0x01 != 0 {
+                        min += 255;
+                    }
+                    if desc[4] & 0x02 != 0 {
+                        max += 255;
+                    }
+                    out.extensions.add_vrr_range(min, max);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// The mode to show for this monitor. Extensions can carry modes the base block has no room for (4K@144
+    /// doesn't fit an 18 byte DTD), so take the largest one and prefer higher refresh rates at that size.
+    /// Tiled monitors report a single tile, those get stitched back together
+    pub fn best_timing(&self) -> Option<DetailedTiming> {
+        let mut best = self.preferred_timing.clone();
+        for timing in self.extensions.timings.iter() {
+            let better = match &best {
+                Some(b) => {
+                    let area = timing.width * timing.height;
+                    let best_area = b.width * b.height;
+                    area > best_area || (area == best_area && timing.refresh > b.refresh + 0.5)
+                }
+                None => true,
+            };
+            if better {
+                best = Some(timing.clone());
+            }
+        }
+
+        if let (Some((h_tiles, v_tiles, tile_w, tile_h)), Some(b)) = (self.extensions.tiles, best.as_mut()) {
+            if b.width == tile_w && b.height == tile_h {
+                b.width *= h_tiles;
+                b.height *= v_tiles;
+            }
+        }
+
+        best
+    }
+
+    pub fn vendor(&self) -> String {
+        vendor_name(self.manufacturer_id.as_str())
+            .unwrap_or(self.manufacturer_id.as_str())
+            .to_string()
+    }
+
+    /// Monitor name as shown on the Displays line, the name descriptor usually already contains the brand
+    pub fn display_name(&self) -> String {
+        let vendor = self.vendor();
+        match &self.monitor_name {
+            Some(name) if name.to_lowercase().contains(vendor.to_lowercase().as_str()) => name.clone(),
+            Some(name) => format!("{} {}", vendor, name),
+            None => format!("{} {:04X}", vendor, self.product_code),
+        }
+    }
+
+    /// Diagonal size in inches, None for projectors and anything else that doesn't report a size
+    pub fn diagonal_inches(&self) -> Option<f32> {
+        if self.width_cm == 0 || self.height_cm == 0 {
+            return None;
+        }
+        let w = self.width_cm as f32;
+        let h = self.height_cm as f32;
+        Some((w * w + h * h).sqrt() / 2.54)
+    }
+
+    pub fn serial(&self) -> Option<String> {
+        if self.serial_string.is_some() {
+            self.serial_string.clone()
+        } else if self.serial_number != 0 {
+            Some(self.serial_number.to_string())
+        } else {
+            None
+        }
+    }
+
+    pub fn manufacture_date(&self) -> String {
+        match self.manufacture_week {
+            0 => format!("{}", self.manufacture_year),
+            0xFF => format!("model year {}", self.manufacture_year),
+            week => format!("week {} {}", week, self.manufacture_year),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Builds a base block for a Dell U2720Q, shared with the extension tests
+    pub fn sample_edid() -> Vec<u8> {
+        let mut edid = vec![0u8; 128];
+        edid[0..8].copy_from_slice(&EDID_HEADER_PATTERN);
+        // "DEL"
+        edid[8] = 0x10;
+        edid[9] = 0xAC;
+        edid[10..12].copy_from_slice(&0xA0FBu16.to_le_bytes());
+        edid[12..16].copy_from_slice(&0x4C4B4C33u32.to_le_bytes());
+        edid[16] = 14;
+        edid[17] = 30;
+        edid[18] = 1;
+        edid[19] = 4;
+        edid[21] = 60;
+        edid[22] = 34;
+
+        // 3840x2160 CVT-RB, 533.25MHz
+        let dtd = [0x4D, 0xD0, 0x00, 0xA0, 0xF0, 0x70, 0x3E, 0x80, 0x30, 0x20, 0x35, 0x00, 0x54, 0x4F, 0x21, 0x00, 0x00, 0x1A];
+        edid[54..72].copy_from_slice(&dtd);
+
+        let mut name = [0u8; 18];
+        name[3] = 0xFC;
+        name[5..18].copy_from_slice(b"DELL U2720Q\n ");
+        edid[72..90].copy_from_slice(&name);
+
+        let mut serial = [0u8; 18];
+        serial[3] = 0xFF;
+        serial[5..18].copy_from_slice(b"ABC1234\n     ");
+        edid[90..108].copy_from_slice(&serial);
+
+        edid[108 + 3] = 0x10;
+
+        fix_checksum(&mut edid[..128]);
+        edid
+    }
+
+    pub fn fix_checksum(block: &mut [u8]) {
+        block[127] = 0;
+        let sum = block.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+        block[127] = 0u8.wrapping_sub(sum);
+    }
+
+    #[test]
+    fn test_parse_base_block() {
+        let edid = Edid::parse(&sample_edid()).unwrap();
+        assert_eq!(edid.manufacturer_id, "DEL");
+        assert_eq!(edid.vendor(), "Dell");
+        assert_eq!(edid.display_name(), "DELL U2720Q");
+        assert_eq!(edid.serial(), Some("ABC1234".to_string()));
+        assert_eq!(edid.manufacture_date(), "week 14 2020");
+        assert_eq!(edid.diagonal_inches().unwrap().round(), 27.0);
+
+        let timing = edid.preferred_timing.unwrap();
+        assert_eq!((timing.width, timing.height), (3840, 2160));
+        assert_eq!(timing.refresh.round(), 60.0);
+    }
+
+    #[test]
+    fn test_interlaced_timing() {
+        // CTA-861 1080i60, vertical values are per field
+        let dtd = [
+            0x01, 0x1D, 0x80, 0x18, 0x71, 0x1C, 0x16, 0x20, 0x58, 0x2C, 0x25, 0x00, 0xC4, 0x8E, 0x21, 0x00, 0x00, 0x9E,
+        ];
+        let timing = parse_detailed_timing(&dtd).unwrap();
+        assert_eq!((timing.width, timing.height), (1920, 1080));
+        assert!(timing.interlaced);
+        assert_eq!(timing.to_string(), "1920x1080i@60Hz");
+    }
+
+    #[test]
+    fn test_rejects_bad_data() {
+        assert_eq!(Edid::parse(&[]).unwrap_err().err_type, EdidParseErrorType::EarlyEof);
+
+        let mut edid = sample_edid();
+        edid[0] = 0xFF;
+        assert_eq!(Edid::parse(&edid).unwrap_err().err_type, EdidParseErrorType::InvalidHeader);
+
+        let mut edid = sample_edid();
+        edid[20] ^= 0x01;
+        assert_eq!(Edid::parse(&edid).unwrap_err().err_type, EdidParseErrorType::BadChecksum);
+    }
+}
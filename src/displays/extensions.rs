@@ -0,0 +1,394 @@
+// EDID extension block parsers, CTA-861 for HDR/deep color/FreeSync and DisplayID for tiled and high refresh modes
+// https://glenwing.github.io/docs/ has copies of most of the specs, edid-decode is the reference for the vendor blocks
+
+use super::edid::{parse_detailed_timing, DetailedTiming};
+
+const CTA_EXTENSION_TAG: u8 = 0x02;
+const DISPLAYID_EXTENSION_TAG: u8 = 0x70;
+
+// Vendor specific data block OUIs, stored little endian in the EDID
+const OUI_HDMI: u32 = 0x000C03;
+const OUI_HDMI_FORUM: u32 = 0xC45DD8;
+const OUI_AMD: u32 = 0x00001A;
+const OUI_HDR10_PLUS: u32 = 0x90848B;
+const OUI_DOLBY: u32 = 0x00D046;
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionInfo {
+    // Transfer functions from the CTA HDR static metadata block
+    pub eotf_pq: bool,
+    pub eotf_hlg: bool,
+    pub hdr10_plus: bool,
+    pub dolby_vision: bool,
+    // Desired content max luminance in cd/m²
+    pub max_luminance: Option<f32>,
+    pub vrr_range: Option<(u16, u16)>,
+    pub bit_depth: Option<u8>,
+    // Every detailed timing found in the extensions
+    pub timings: Vec<DetailedTiming>,
+    // Horizontal and vertical tile count plus the size of one tile
+    pub tiles: Option<(u32, u32, u32, u32)>,
+}
+
+impl ExtensionInfo {
+    pub fn add_bit_depth(&mut self, depth: u8) {
+        match self.bit_depth {
+            Some(d) if d >= depth => {}
+            _ => self.bit_depth = Some(depth),
+        }
+    }
+
+    // Vendor ranges are more trustworthy than generic ones, so the first one found sticks
+    pub fn add_vrr_range(&mut self, min: u16, max: u16) {
+        if self.vrr_range.is_none() && min > 0 && max > min {
+            self.vrr_range = Some((min, max));
+        }
+    }
+
+    /// Badges shown after the monitor name, e.g. `HDR10, VRR 48–165Hz, 10-bit`
+    pub fn badges(&self) -> Vec<String> {
+        let mut badges: Vec<String> = vec![];
+
+        if self.eotf_pq {
+            match self.max_luminance {
+                Some(nits) => badges.push(format!("HDR10 {:.0}nits", nits)),
+                None => badges.push("HDR10".to_string()),
+            }
+        }
+        if self.hdr10_plus {
+            badges.push("HDR10+".to_string());
+        }
+        if self.dolby_vision {
+            badges.push("Dolby Vision".to_string());
+        }
+        if self.eotf_hlg {
+            badges.push("HLG".to_string());
+        }
+        if let Some((min, max)) = self.vrr_range {
+            badges.push(format!("VRR {}–{}Hz", min, max));
+        }
+        if let Some(depth) = self.bit_depth {
+            if depth > 8 {
+                badges.push(format!("{}-bit", depth));
+            }
+        }
+        if let Some((h, v, _, _)) = self.tiles {
+            badges.push(format!("Tiled {}x{}", h, v));
+        }
+
+        badges
+    }
+}
+
+fn block_checksum_ok(block: &[u8]) -> bool {
+    block.iter().fold(0u8, |acc, b| acc.wrapping_add(*b)) == 0
+}
+
+fn read_oui(data: &[u8]) -> Option<u32> {
+    if data.len() < 3 {
+        return None;
+    }
+    Some(data[0] as u32 | (data[1] as u32) << 8 | (data[2] as u32) << 16)
+}
+
+// Luminance code values are 50 * 2^(cv/32) cd/m²
+fn decode_luminance(cv: u8) -> f32 {
+    50.0 * 2f32.powf(cv as f32 / 32.0)
+}
+
+fn parse_cta_vendor_block(payload: &[u8], info: &mut ExtensionInfo) {
+    let Some(oui) = read_oui(payload) else {
+        return;
+    };
+    let x = &payload[3..];
+
+    match oui {
+        OUI_HDMI => {
+            // Byte 2 after the physical address holds the deep color flags
+            if x.len() > 2 {
+                if x[2] & 0x40 != 0 {
+                    info.add_bit_depth(16);
+                } else if x[2] & 0x20 != 0 {
+                    info.add_bit_depth(12);
+                } else if x[2] & 0x10 != 0 {
+                    info.add_bit_depth(10);
+                }
+            }
+        }
+        OUI_HDMI_FORUM => {
+            // 4:2:0 deep color flags
+            if x.len() > 3 {
+                if x[3] & 0x04 != 0 {
+                    info.add_bit_depth(16);
+                } else if x[3] & 0x02 != 0 {
+                    info.add_bit_depth(12);
+                } else if x[3] & 0x01 != 0 {
+                    info.add_bit_depth(10);
+                }
+            }
+            // HDMI 2.1 VRR, 6 bit min with the top two bits of the 10 bit max above it, then the low max byte
+            if x.len() > 6 {
+                let min = (x[5] & 0x3F) as u16;
+                let max = ((x[5] as u16 & 0xC0) << 2) | x[6] as u16;
+                info.add_vrr_range(min, max);
+            }
+        }
+        OUI_AMD => {
+            // FreeSync, reverse engineered layout: version major, minor, min refresh, max refresh
+            if x.len() > 3 {
+                info.add_vrr_range(x[2] as u16, x[3] as u16);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_cta_extended_block(payload: &[u8], info: &mut ExtensionInfo) {
+    let Some((ext_tag, x)) = payload.split_first() else {
+        return;
+    };
+
+    match ext_tag {
+        // Vendor specific video data block
+        0x01 => match read_oui(x) {
+            Some(OUI_HDR10_PLUS) => info.hdr10_plus = true,
+            Some(OUI_DOLBY) => info.dolby_vision = true,
+            _ => {}
+        },
+        // HDR static metadata
+        0x06 => {
+            if let Some(eotfs) = x.first() {
+                info.eotf_pq |= eotfs & 0x04 != 0;
+                info.eotf_hlg |= eotfs & 0x08 != 0;
+            }
+            if let Some(cv) = x.get(2) {
+                if *cv != 0 {
+                    info.max_luminance = Some(decode_luminance(*cv));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_cta(block: &[u8], info: &mut ExtensionInfo) {
+    let dtd_offset = (block[2] as usize).min(127);
+
+    // Data block collection sits between the header and the first DTD
+    if dtd_offset >= 4 {
+        let mut i = 4;
+        while i < dtd_offset {
+            let tag = block[i] >> 5;
+            let len = (block[i] & 0x1F) as usize;
+            let end = (i + 1 + len).min(dtd_offset);
+            let payload = &block[i + 1..end];
+
+            match tag {
+                3 => parse_cta_vendor_block(payload, info),
+                7 => parse_cta_extended_block(payload, info),
+                _ => {}
+            }
+            i += 1 + len;
+        }
+    }
+
+    // Trailing DTDs, padding is all zeroes which parse_detailed_timing skips
+    if dtd_offset > 0 {
+        let mut i = dtd_offset;
+        while i + 18 <= 127 {
+            if let Some(timing) = parse_detailed_timing(&block[i..i + 18]) {
+                info.timings.push(timing);
+            }
+            i += 18;
+        }
+    }
+}
+
+// DisplayID type I (1.x) and type VII (2.x) timings share a 20 byte layout, only the clock unit differs
+fn parse_displayid_timing(data: &[u8], clock_unit_hz: u64) -> DetailedTiming {
+    let pixel_clock = (data[0] as u64 | (data[1] as u64) << 8 | (data[2] as u64) << 16) + 1;
+    let h_active = u16::from_le_bytes([data[4], data[5]]) as u32 + 1;
+    let h_blank = u16::from_le_bytes([data[6], data[7]]) as u32 + 1;
+    let v_active = u16::from_le_bytes([data[12], data[13]]) as u32 + 1;
+    let v_blank = u16::from_le_bytes([data[14], data[15]]) as u32 + 1;
+    let interlaced = data[3] & 0x10 != 0;
+
+    let total = (h_active + h_blank) as u64 * (v_active + v_blank) as u64;
+    DetailedTiming {
+        width: h_active,
+        height: v_active,
+        refresh: (pixel_clock * clock_unit_hz) as f32 / total as f32,
+        interlaced,
+    }
+}
+
+fn parse_displayid(block: &[u8], info: &mut ExtensionInfo) {
+    // The section starts after the extension tag, byte 2 is the payload length
+    let section_end = (5 + block[2] as usize).min(127);
+
+    let mut i = 5;
+    while i + 3 <= section_end {
+        let tag = block[i];
+        let revision = block[i + 1] & 0x07;
+        let len = block[i + 2] as usize;
+        let end = (i + 3 + len).min(section_end);
+        let payload = &block[i + 3..end];
+
+        match tag {
+            // Type I (10kHz) and type VII (1kHz) detailed timings
+            0x03 | 0x22 => {
+                let unit = if tag == 0x03 {
+                    10_000
+                } else {
+                    1_000
+                };
+                for desc in payload.chunks_exact(20) {
+                    info.timings.push(parse_displayid_timing(desc, unit));
+                }
+            }
+            // 1.x video timing range limits: pixel clock, horizontal frequency and blanking come first
+            0x09 => {
+                if payload.len() >= 12 {
+                    info.add_vrr_range(payload[10] as u16, payload[11] as u16);
+                }
+            }
+            // 2.x dynamic video timing range limits, revision 1 added two more bits to the max refresh
+            0x25 => {
+                if payload.len() >= 8 {
+                    let min = payload[6] as u16;
+                    let mut max = payload[7] as u16;
+                    if revision >= 1 && payload.len() >= 9 {
+                        max |= (payload[8] as u16 & 0x03) << 8;
+                    }
+                    info.add_vrr_range(min, max);
+                }
+            }
+            // Tiled display topology, 1.x and 2.x
+            0x12 | 0x28 => {
+                if payload.len() >= 8 {
+                    let h_tiles = ((payload[1] >> 4) as u32 | ((payload[3] >> 6) as u32) << 4) + 1;
+                    let v_tiles = ((payload[1] & 0x0F) as u32 | (((payload[3] >> 4) & 0x03) as u32) << 4) + 1;
+                    let tile_w = u16::from_le_bytes([payload[4], payload[5]]) as u32 + 1;
+                    let tile_h = u16::from_le_bytes([payload[6], payload[7]]) as u32 + 1;
+                    info.tiles = Some((h_tiles, v_tiles, tile_w, tile_h));
+                }
+            }
+            _ => {}
+        }
+
+        // Zero tag with zero length is padding
+        if tag == 0 && len == 0 {
+            break;
+        }
+        i += 3 + len;
+    }
+}
+
+/// Parses every extension block after the base block, blocks with a bad checksum are skipped
+pub fn parse_extensions(data: &[u8]) -> ExtensionInfo {
+    let mut info = ExtensionInfo::default();
+
+    for block in data.chunks_exact(128).skip(1) {
+        if !block_checksum_ok(block) {
+            continue;
+        }
+        match block[0] {
+            CTA_EXTENSION_TAG => parse_cta(block, &mut info),
+            DISPLAYID_EXTENSION_TAG => parse_displayid(block, &mut info),
+            _ => {}
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::displays::edid::tests::fix_checksum;
+
+    #[test]
+    fn test_cta_hdr_vrr_deep_color() {
+        let mut block = vec![0u8; 128];
+        block[0] = CTA_EXTENSION_TAG;
+        block[1] = 3;
+
+        let data_blocks: Vec<u8> = vec![
+            // HDMI VSDB with 30 and 36 bit deep color
+            0x66, 0x03, 0x0C, 0x00, 0x10, 0x00, 0x30,
+            // AMD FreeSync 48-165Hz
+            0x68, 0x1A, 0x00, 0x00, 0x01, 0x01, 0x30, 0xA5, 0x00,
+            // HDR static metadata, SDR + PQ + HLG, max luminance cv 114
+            0xE5, 0x06, 0x0D, 0x01, 0x72, 0x00,
+        ];
+        block[4..4 + data_blocks.len()].copy_from_slice(&data_blocks);
+        block[2] = (4 + data_blocks.len()) as u8;
+        fix_checksum(&mut block);
+
+        let mut edid = crate::displays::edid::tests::sample_edid();
+        edid[126] = 1;
+        fix_checksum(&mut edid[..128]);
+        edid.extend(block);
+
+        let info = parse_extensions(&edid);
+        assert!(info.eotf_pq && info.eotf_hlg);
+        assert_eq!(info.vrr_range, Some((48, 165)));
+        assert_eq!(info.bit_depth, Some(12));
+        assert_eq!(info.max_luminance.unwrap().round(), 591.0);
+        assert_eq!(info.badges(), vec!["HDR10 591nits", "HLG", "VRR 48–165Hz", "12-bit"]);
+    }
+
+    #[test]
+    fn test_hdmi_forum_vrr() {
+        // HF-VSDB payload: OUI, version, max TMDS rate, SCDC flags, 30 bit 4:2:0, FRL, VRR 48-300Hz, DSC
+        let payload: Vec<u8> = vec![0xD8, 0x5D, 0xC4, 0x01, 0x78, 0x80, 0x01, 0x00, 0x70, 0x2C, 0x00];
+
+        let mut info = ExtensionInfo::default();
+        parse_cta_vendor_block(&payload, &mut info);
+        assert_eq!(info.vrr_range, Some((48, 300)));
+        assert_eq!(info.bit_depth, Some(10));
+    }
+
+    fn displayid_block(data_block: &[u8]) -> Vec<u8> {
+        let mut block = vec![0u8; 128];
+        block[0] = DISPLAYID_EXTENSION_TAG;
+        block[1] = 0x12;
+        block[5..5 + data_block.len()].copy_from_slice(data_block);
+        block[2] = data_block.len() as u8;
+        fix_checksum(&mut block);
+        block
+    }
+
+    #[test]
+    fn test_displayid_tiles() {
+        // Tiled topology, 2x1 tiles of 2560x2880
+        let block = displayid_block(&[0x12, 0x00, 0x16, 0x82, 0x10, 0x00, 0x00, 0xFF, 0x09, 0x3F, 0x0B]);
+        let mut info = ExtensionInfo::default();
+        parse_displayid(&block, &mut info);
+        assert_eq!(info.tiles, Some((2, 1, 2560, 2880)));
+    }
+
+    #[test]
+    fn test_displayid_range_limits() {
+        // 1.x range limits: 25-600MHz pixel clock, 30-160kHz horizontal, 80px hblank, 48-144Hz, 10 line vblank
+        let block = displayid_block(&[
+            0x09, 0x00, 0x0F, 0xC4, 0x09, 0x00, 0x60, 0xEA, 0x00, 0x1E, 0xA0, 0x50, 0x00, 0x30, 0x90, 0x0A, 0x00, 0x00,
+        ]);
+        let mut info = ExtensionInfo::default();
+        parse_displayid(&block, &mut info);
+        assert_eq!(info.vrr_range, Some((48, 144)));
+
+        // 2.x dynamic range limits, the extra max bits only count from revision 1
+        let dynamic = [0x25, 0x01, 0x09, 0xC4, 0x09, 0x00, 0x60, 0xEA, 0x00, 0x30, 0x2C, 0x01];
+        let mut info = ExtensionInfo::default();
+        parse_displayid(&displayid_block(&dynamic), &mut info);
+        assert_eq!(info.vrr_range, Some((48, 300)));
+
+        let mut revision_0 = dynamic;
+        revision_0[1] = 0x00;
+        revision_0[10] = 0xF0;
+        let mut info = ExtensionInfo::default();
+        parse_displayid(&displayid_block(&revision_0), &mut info);
+        assert_eq!(info.vrr_range, Some((48, 240)));
+    }
+}
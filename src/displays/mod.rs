@@ -1,48 +1,222 @@
-use std::fs::{self, read_dir};
+mod edid;
+mod extensions;
+mod randr;
+mod wayland;
+
+use std::fs;
 use regex::Regex;
 
-pub fn get_displays() -> String {
-    let output_pattern = Regex::new(r"(?i)^card\d+-[A-Z59_-]+?-\d+$").unwrap();
-
-    let mut monitors: Vec<String> = vec![];
-
-    // Iterate over DRM devices in sysfs
-    for entry in read_dir("/sys/class/drm").unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-        let filename = entry.file_name().into_string().unwrap();
-
-        // Check if the directory matches the output pattern and has a `status` file
-        if output_pattern.is_match(&filename) && path.join("status").is_file() && path.join("edid").is_file() {
-            // Read the connection status of the display
-            let status = fs::read_to_string(path.join("status")).unwrap();
-            if status.trim() == "connected" {
-                let modes_path = path.join("modes");
-                if let Ok(modes) = fs::read_to_string(modes_path) {
-                    // The first line usually contains the maximum resolution
-                    if let Some(max_resolution) = modes.lines().next() {
-                        //println!("Max resolution: {}", max_resolution);
-                        monitors.push(max_resolution.to_string());
-                    }
-                }
-            }
+use edid::Edid;
+use randr::get_randr_outputs;
+use wayland::get_wayland_outputs;
+
+use crate::{drm::{DrmCard, DrmConnector}, gpus::Gpu, utils::format_tree};
+
+// What the display server is currently doing with an output, from either Wayland or RandR
+struct LiveOutput {
+    // DRM style connector name used for matching, None when the server doesn't tell
+    connector: Option<String>,
+    label: String,
+    description: String,
+}
+
+// The Wayland compositor if there is one, otherwise the X server when `randr` allows asking it
+fn get_live_outputs(randr: bool) -> Vec<LiveOutput> {
+    if let Some(outputs) = get_wayland_outputs() {
+        return outputs
+            .iter()
+            .map(|o| LiveOutput {
+                connector: o.name.clone(),
+                label: o.name.clone().unwrap_or_else(|| format!("{} {}", o.make, o.model).trim().to_string()),
+                description: o.describe(),
+            })
+            .collect();
+    }
+    if !randr {
+        return vec![];
+    }
+
+    get_randr_outputs()
+        .unwrap_or_default()
+        .iter()
+        .map(|o| LiveOutput {
+            connector: Some(o.name.clone()),
+            label: o.name.clone(),
+            description: o.describe(),
+        })
+        .collect()
+}
+
+// X drivers drop the `-A` from `HDMI-A-1` and `DVI-D-1`
+fn same_connector(drm: &str, live: &str) -> bool {
+    drm == live || drm.replacen("-A-", "-", 1) == live || drm.replacen("-D-", "-", 1) == live
+}
+
+fn format_monitor(edid: &Edid, modes: Option<&str>, show_details: bool) -> String {
+    let mut out = edid.display_name();
+
+    if let Some(inches) = edid.diagonal_inches() {
+        out += format!(" {:.0}\"", inches).as_str();
+    }
+
+    if let Some(timing) = edid.best_timing() {
+        out += format!(" {}", timing).as_str();
+    } else if let Some(mode) = modes {
+        out += format!(" {}", mode).as_str();
+    }
+
+    let badges = edid.extensions.badges();
+    if !badges.is_empty() {
+        out += format!(" [{}]", badges.join(", ")).as_str();
+    }
+
+    if show_details {
+        let mut details = vec![format!("made {}", edid.manufacture_date())];
+        if let Some(serial) = edid.serial() {
+            details.push(format!("S/N {}", serial));
         }
+        out += format!(" ({})", details.join(", ")).as_str();
+    }
+
+    out
+}
+
+// `DP-2: DELL U2720Q 27" 3840x2160@60Hz`, None if the connector has neither a usable EDID nor modes.
+// Virtual GPUs (virtio-gpu, QXL, VMware) often have an empty or missing EDID, those fall back to the mode list
+fn describe_connector(
+    connector: &DrmConnector,
+    card: &DrmCard,
+    live: Option<&LiveOutput>,
+    show_details: bool,
+) -> Option<String> {
+    // The first line usually contains the maximum resolution
+    let modes = fs::read_to_string(connector.path.join("modes")).unwrap_or_default();
+    let max_resolution = modes.lines().next();
+
+    let monitor = match fs::read(connector.path.join("edid")).map(|data| Edid::parse(&data)) {
+        Ok(Ok(edid)) => format_monitor(&edid, max_resolution, show_details),
+        _ => match &card.driver {
+            Some(driver) => format!("{} ({})", max_resolution?, driver),
+            None => max_resolution?.to_string(),
+        },
     };
 
-    if monitors.len() == 0 {
-        return "None found".to_string();
-    } else if monitors.len() == 1 {
-        return "╰ ".to_string() + monitors[0].as_str();
-    } else {
-        let mut out = "".to_string();
-        for (i, mon) in monitors.iter().enumerate() {
-            if i == monitors.len()-1 {
-                out.push_str("\n╰ ");
-            } else {
-                out.push_str("\n│ ");
-            }
-            out.push_str(mon.as_str());
+    match live {
+        Some(output) => Some(format!("{}: {} (current: {})", connector.name, monitor, output.description)),
+        None => Some(format!("{}: {}", connector.name, monitor)),
+    }
+}
+
+// Framebuffers for machines without KMS, e.g. `fb0: 1024x768 (EFI VGA)`. Framebuffers emulated by a DRM
+// driver are skipped when there are DRM cards since they only mirror whatever the card has
+fn get_framebuffers(has_drm: bool) -> Vec<String> {
+    let mode_pattern = Regex::new(r"(\d+x\d+)[pi]?-(\d+)").unwrap();
+    let mut fbs: Vec<String> = vec![];
+
+    let Ok(entries) = fs::read_dir("/sys/class/graphics") else {
+        return fbs;
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("fb"))
+        .map(|e| e.path())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let name = fs::read_to_string(path.join("name")).unwrap_or_default().trim().to_string();
+        if has_drm && name.ends_with("drmfb") {
+            continue;
+        }
+
+        // `modes` looks like `U:1024x768p-0`, the trailing number is the refresh rate when known
+        let modes = fs::read_to_string(path.join("modes")).unwrap_or_default();
+        let resolution = match mode_pattern.captures(modes.lines().next().unwrap_or_default()) {
+            Some(c) if &c[2] != "0" => Some(format!("{}@{}Hz", &c[1], &c[2])),
+            Some(c) => Some(c[1].to_string()),
+            // `virtual_size` is `1024,768`
+            None => fs::read_to_string(path.join("virtual_size"))
+                .ok()
+                .map(|s| s.trim().replace(',', "x")),
+        };
+        let Some(resolution) = resolution else {
+            continue;
+        };
+
+        let fb = path.file_name().unwrap().to_string_lossy().to_string();
+        if name.is_empty() {
+            fbs.push(format!("{}: {}", fb, resolution));
+        } else {
+            fbs.push(format!("{}: {} ({})", fb, resolution, name));
+        }
+    }
+
+    fbs
+}
+
+/// `randr` is false when the X server shouldn't be asked, e.g. a forwarded `ssh -X` display belongs to the client
+pub fn get_displays(show_details: bool, randr: bool, gpus: &[Gpu], cards: &[DrmCard]) -> String {
+    // (card, monitors on that card)
+    let mut groups: Vec<(&DrmCard, Vec<String>)> = vec![];
+    // Live outputs are named after their connector so they can be matched up with the DRM side
+    let mut live_outputs = get_live_outputs(randr);
+
+    for card in cards {
+        let monitors: Vec<String> = card
+            .connected()
+            .filter_map(|c| {
+                let live = live_outputs
+                    .iter()
+                    .position(|o| o.connector.as_deref().is_some_and(|n| same_connector(&c.name, n)))
+                    .map(|i| live_outputs.remove(i));
+                describe_connector(c, card, live.as_ref(), show_details)
+            })
+            .collect();
+        if !monitors.is_empty() {
+            groups.push((card, monitors));
+        }
+    }
+
+    // Anything left over has no DRM connector, e.g. nested compositors, Xvfb or X drivers with their own naming
+    let unmatched: Vec<String> = live_outputs
+        .iter()
+        .map(|o| format!("{}: {}", o.label, o.description))
+        .collect();
+    if groups.is_empty() && !unmatched.is_empty() {
+        return format_tree(&unmatched);
+    }
+
+    if groups.is_empty() {
+        let fbs = get_framebuffers(!cards.is_empty());
+        if fbs.is_empty() {
+            return "Headless".to_string();
         }
-        return out.trim().to_string();
+        return format_tree(&fbs);
+    }
+
+    // Single GPU systems don't need to be told which GPU drives their monitors
+    if cards.len() <= 1 {
+        let mut monitors = groups.remove(0).1;
+        monitors.extend(unmatched);
+        return format_tree(&monitors);
     }
+
+    let mut items: Vec<String> = groups
+        .into_iter()
+        .map(|(card, monitors)| {
+            let gpu_name = gpus
+                .iter()
+                .find(|g| g.card.as_ref() == Some(&card.name))
+                .map(|g| g.name.clone());
+            let header = match (gpu_name, &card.pci_slot) {
+                (Some(name), _) => format!("{} [{}]", name, card.name),
+                (None, Some(slot)) => format!("{} [{}]", slot, card.name),
+                (None, None) => card.name.clone(),
+            };
+            header + "\n" + format_tree(&monitors).as_str()
+        })
+        .collect();
+    items.extend(unmatched);
+
+    format_tree(&items)
 }
@@ -0,0 +1,184 @@
+// Live output state from the X server through RandR, the X11 counterpart to wayland.rs
+use std::env;
+
+use x11rb::{
+    connection::Connection,
+    protocol::randr::{self, ConnectionExt as _, ModeFlag, Rotation},
+    rust_connection::RustConnection,
+};
+
+#[derive(Debug, Clone)]
+pub struct RandrOutput {
+    // Output name as the X driver calls it, e.g. `DP-2` or `HDMI-1`
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    pub refresh: f32,
+    pub rotation: Rotation,
+    pub primary: bool,
+    // Physical width reported by the server, zero when unknown
+    pub mm_width: u32,
+}
+
+impl RandrOutput {
+    fn rotated(&self) -> bool {
+        self.rotation.contains(Rotation::ROTATE90) || self.rotation.contains(Rotation::ROTATE270)
+    }
+
+    /// Horizontal DPI, None when the server doesn't know the physical size
+    pub fn dpi(&self) -> Option<f32> {
+        // The CRTC size is already rotated, the physical size isn't
+        let px = if self.rotated() {
+            self.height
+        } else {
+            self.width
+        };
+        if self.mm_width == 0 || px == 0 {
+            return None;
+        }
+        Some(px as f32 / (self.mm_width as f32 / 25.4))
+    }
+
+    /// `3840x2160@60Hz, 163 DPI, rotated 90°, primary`
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = vec![];
+
+        if (self.refresh - self.refresh.round()).abs() < 0.01 {
+            parts.push(format!("{}x{}@{:.0}Hz", self.width, self.height, self.refresh));
+        } else {
+            parts.push(format!("{}x{}@{:.2}Hz", self.width, self.height, self.refresh));
+        }
+
+        if let Some(dpi) = self.dpi() {
+            parts.push(format!("{:.0} DPI", dpi));
+        }
+
+        let rotation = if self.rotation.contains(Rotation::ROTATE90) {
+            Some("rotated 90°")
+        } else if self.rotation.contains(Rotation::ROTATE180) {
+            Some("rotated 180°")
+        } else if self.rotation.contains(Rotation::ROTATE270) {
+            Some("rotated 270°")
+        } else {
+            None
+        };
+        if let Some(rotation) = rotation {
+            parts.push(rotation.to_string());
+        }
+        if self.rotation.contains(Rotation::REFLECT_X) || self.rotation.contains(Rotation::REFLECT_Y) {
+            parts.push("flipped".to_string());
+        }
+
+        if self.primary {
+            parts.push("primary".to_string());
+        }
+
+        parts.join(", ")
+    }
+}
+
+fn refresh_rate(mode: &randr::ModeInfo) -> f32 {
+    let mut vtotal = mode.vtotal as f32;
+    if mode.mode_flags.contains(ModeFlag::DOUBLE_SCAN) {
+        vtotal *= 2.0;
+    }
+    if mode.mode_flags.contains(ModeFlag::INTERLACE) {
+        vtotal /= 2.0;
+    }
+    if mode.htotal == 0 || vtotal == 0.0 {
+        return 0.0;
+    }
+    mode.dot_clock as f32 / (mode.htotal as f32 * vtotal)
+}
+
+/// Every output with an active CRTC, None when not on X11 or RandR isn't available
+pub fn get_randr_outputs() -> Option<Vec<RandrOutput>> {
+    env::var("DISPLAY").ok()?;
+
+    let (conn, screen_num) = RustConnection::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+
+    let resources = conn.randr_get_screen_resources_current(root).ok()?.reply().ok()?;
+    let primary = conn
+        .randr_get_output_primary(root)
+        .ok()
+        .and_then(|c| c.reply().ok())
+        .map(|r| r.output);
+
+    let mut outputs: Vec<RandrOutput> = vec![];
+    for output in resources.outputs.iter() {
+        let Some(info) = conn
+            .randr_get_output_info(*output, resources.config_timestamp)
+            .ok()
+            .and_then(|c| c.reply().ok())
+        else {
+            continue;
+        };
+        // Disconnected outputs and connected but disabled ones have no CRTC
+        if info.connection != randr::Connection::CONNECTED || info.crtc == 0 {
+            continue;
+        }
+
+        let Some(crtc) = conn
+            .randr_get_crtc_info(info.crtc, resources.config_timestamp)
+            .ok()
+            .and_then(|c| c.reply().ok())
+        else {
+            continue;
+        };
+        let Some(mode) = resources.modes.iter().find(|m| m.id == crtc.mode) else {
+            continue;
+        };
+
+        outputs.push(RandrOutput {
+            name: String::from_utf8_lossy(&info.name).to_string(),
+            width: crtc.width,
+            height: crtc.height,
+            refresh: refresh_rate(mode),
+            rotation: crtc.rotation,
+            primary: primary == Some(*output),
+            mm_width: info.mm_width,
+        });
+    }
+
+    Some(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(width: u16, height: u16, rotation: Rotation, mm_width: u32) -> RandrOutput {
+        RandrOutput { name: "DP-1".to_string(), width, height, refresh: 60.0, rotation, primary: false, mm_width }
+    }
+
+    #[test]
+    fn describe_outputs() {
+        let monitor = RandrOutput { primary: true, ..output(3840, 2160, Rotation::ROTATE0, 597) };
+        assert_eq!(monitor.describe(), "3840x2160@60Hz, 163 DPI, primary");
+
+        // The physical width belongs to the unrotated panel
+        let portrait = output(1080, 1920, Rotation::ROTATE90, 527);
+        assert_eq!(portrait.dpi().map(|d| d.round()), Some(93.0));
+        assert_eq!(portrait.describe(), "1080x1920@60Hz, 93 DPI, rotated 90°");
+
+        let projector = RandrOutput {
+            refresh: 59.94,
+            ..output(1920, 1080, Rotation::ROTATE0 | Rotation::REFLECT_X, 0)
+        };
+        assert_eq!(projector.dpi(), None);
+        assert_eq!(projector.describe(), "1920x1080@59.94Hz, flipped");
+    }
+
+    #[test]
+    fn refresh_rates() {
+        let mode = randr::ModeInfo { dot_clock: 148_500_000, htotal: 2200, vtotal: 1125, ..Default::default() };
+        assert_eq!(refresh_rate(&mode), 60.0);
+
+        // 1080i sends half the lines per field
+        let interlaced = randr::ModeInfo { dot_clock: 74_250_000, mode_flags: ModeFlag::INTERLACE, ..mode };
+        assert_eq!(refresh_rate(&interlaced), 60.0);
+
+        assert_eq!(refresh_rate(&randr::ModeInfo::default()), 0.0);
+    }
+}
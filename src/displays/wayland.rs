@@ -0,0 +1,233 @@
+// Live output state straight from the compositor. sysfs only knows what a monitor supports, this is what is
+// actually in use: current mode, scale and rotation
+use std::env;
+
+use wayland_client::{
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{wl_output, wl_registry},
+    Connection, Dispatch, QueueHandle, WEnum,
+};
+use wayland_protocols::xdg::xdg_output::zv1::client::{zxdg_output_manager_v1, zxdg_output_v1};
+
+#[derive(Debug, Default, Clone)]
+pub struct WaylandOutput {
+    // Connector name like `DP-2`, only sent by wl_output v4+ and xdg_output v2+
+    pub name: Option<String>,
+    pub make: String,
+    pub model: String,
+    // Current mode in pixels and mHz
+    pub mode: Option<(i32, i32, i32)>,
+    pub integer_scale: i32,
+    pub transform: Option<wl_output::Transform>,
+    // Size in compositor space, used to work out fractional scaling
+    pub logical_size: Option<(i32, i32)>,
+}
+
+impl WaylandOutput {
+    fn rotated(&self) -> bool {
+        matches!(
+            self.transform,
+            Some(wl_output::Transform::_90)
+                | Some(wl_output::Transform::_270)
+                | Some(wl_output::Transform::Flipped90)
+                | Some(wl_output::Transform::Flipped270)
+        )
+    }
+
+    /// Effective scale, fractional scaling only shows up as the ratio between mode and logical size
+    pub fn scale(&self) -> f32 {
+        match (self.mode, self.logical_size) {
+            (Some((w, h, _)), Some((lw, _))) if lw > 0 => {
+                let physical_w = if self.rotated() {
+                    h
+                } else {
+                    w
+                };
+                physical_w as f32 / lw as f32
+            }
+            _ => self.integer_scale.max(1) as f32,
+        }
+    }
+
+    /// `2560x1440@59.95Hz, scale 1.5, logical 1707x960, rotated 90°`
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = vec![];
+
+        if let Some((w, h, refresh)) = self.mode {
+            let hz = refresh as f32 / 1000.0;
+            if (hz - hz.round()).abs() < 0.01 {
+                parts.push(format!("{}x{}@{:.0}Hz", w, h, hz));
+            } else {
+                parts.push(format!("{}x{}@{:.2}Hz", w, h, hz));
+            }
+        }
+
+        let scale = self.scale();
+        if (scale - 1.0).abs() > 0.01 {
+            parts.push(format!("scale {}", (scale * 100.0).round() / 100.0));
+            if let Some((lw, lh)) = self.logical_size {
+                parts.push(format!("logical {}x{}", lw, lh));
+            }
+        }
+
+        let transform = match self.transform {
+            Some(wl_output::Transform::_90) => Some("rotated 90°"),
+            Some(wl_output::Transform::_180) => Some("rotated 180°"),
+            Some(wl_output::Transform::_270) => Some("rotated 270°"),
+            Some(wl_output::Transform::Flipped) => Some("flipped"),
+            Some(wl_output::Transform::Flipped90) => Some("flipped, rotated 90°"),
+            Some(wl_output::Transform::Flipped180) => Some("flipped, rotated 180°"),
+            Some(wl_output::Transform::Flipped270) => Some("flipped, rotated 270°"),
+            _ => None,
+        };
+        if let Some(transform) = transform {
+            parts.push(transform.to_string());
+        }
+
+        parts.join(", ")
+    }
+}
+
+struct State {
+    outputs: Vec<WaylandOutput>,
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // Outputs are bound once up front, hotplug doesn't matter for a one-shot fetch
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, usize> for State {
+    fn event(
+        state: &mut Self,
+        _: &wl_output::WlOutput,
+        event: wl_output::Event,
+        idx: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let output = &mut state.outputs[*idx];
+        match event {
+            wl_output::Event::Geometry { make, model, transform, .. } => {
+                output.make = make;
+                output.model = model;
+                if let WEnum::Value(transform) = transform {
+                    output.transform = Some(transform);
+                }
+            }
+            wl_output::Event::Mode { flags: WEnum::Value(flags), width, height, refresh } => {
+                if flags.contains(wl_output::Mode::Current) {
+                    output.mode = Some((width, height, refresh));
+                }
+            }
+            wl_output::Event::Scale { factor } => output.integer_scale = factor,
+            wl_output::Event::Name { name } => output.name = Some(name),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<zxdg_output_manager_v1::ZxdgOutputManagerV1, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &zxdg_output_manager_v1::ZxdgOutputManagerV1,
+        _: zxdg_output_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // No events
+    }
+}
+
+impl Dispatch<zxdg_output_v1::ZxdgOutputV1, usize> for State {
+    fn event(
+        state: &mut Self,
+        _: &zxdg_output_v1::ZxdgOutputV1,
+        event: zxdg_output_v1::Event,
+        idx: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let output = &mut state.outputs[*idx];
+        match event {
+            zxdg_output_v1::Event::LogicalSize { width, height } => output.logical_size = Some((width, height)),
+            zxdg_output_v1::Event::Name { name } => {
+                if output.name.is_none() {
+                    output.name = Some(name);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Every output the compositor knows about, None when not on Wayland or the connection fails
+pub fn get_wayland_outputs() -> Option<Vec<WaylandOutput>> {
+    env::var("WAYLAND_DISPLAY").ok()?;
+
+    let conn = Connection::connect_to_env().ok()?;
+    let (globals, mut queue) = registry_queue_init::<State>(&conn).ok()?;
+    let qh = queue.handle();
+
+    let mut state = State { outputs: vec![] };
+    let mut wl_outputs: Vec<wl_output::WlOutput> = vec![];
+    for global in globals.contents().clone_list() {
+        if global.interface == "wl_output" {
+            let output = globals
+                .registry()
+                .bind::<wl_output::WlOutput, _, _>(global.name, global.version.min(4), &qh, wl_outputs.len());
+            wl_outputs.push(output);
+            state.outputs.push(WaylandOutput::default());
+        }
+    }
+
+    if let Ok(manager) = globals.bind::<zxdg_output_manager_v1::ZxdgOutputManagerV1, _, _>(&qh, 1..=3, ()) {
+        for (idx, output) in wl_outputs.iter().enumerate() {
+            manager.get_xdg_output(output, &qh, idx);
+        }
+    }
+
+    // Both wl_output and xdg_output send everything on bind, one roundtrip flushes it all
+    queue.roundtrip(&mut state).ok()?;
+
+    Some(state.outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(mode: (i32, i32, i32), integer_scale: i32, logical_size: Option<(i32, i32)>) -> WaylandOutput {
+        WaylandOutput { mode: Some(mode), integer_scale, logical_size, ..Default::default() }
+    }
+
+    #[test]
+    fn fractional_scale() {
+        let laptop = output((2560, 1440, 59951), 2, Some((1707, 960)));
+        assert_eq!((laptop.scale() * 100.0).round() / 100.0, 1.5);
+        assert_eq!(laptop.describe(), "2560x1440@59.95Hz, scale 1.5, logical 1707x960");
+
+        // Without xdg_output only the integer scale is known, 0 means the compositor never sent one
+        assert_eq!(output((3840, 2160, 60000), 2, None).scale(), 2.0);
+        assert_eq!(output((1920, 1080, 60000), 0, None).describe(), "1920x1080@60Hz");
+    }
+
+    #[test]
+    fn rotated_output() {
+        let portrait = WaylandOutput {
+            transform: Some(wl_output::Transform::_90),
+            ..output((2560, 1440, 60000), 1, Some((960, 1707)))
+        };
+        assert_eq!(portrait.scale(), 1.5);
+        assert_eq!(portrait.describe(), "2560x1440@60Hz, scale 1.5, logical 960x1707, rotated 90°");
+    }
+}
@@ -0,0 +1,99 @@
+use std::{fs, path::{Path, PathBuf}};
+use regex::Regex;
+
+pub struct DrmConnector {
+    // Name without the card prefix, e.g. `DP-2` or `HDMI-A-1`
+    pub name: String,
+    pub path: PathBuf,
+}
+
+pub struct DrmCard {
+    // `card0`, `card1`, ...
+    pub name: String,
+    // PCI address like `0000:03:00.0`, None for platform (ARM SoC) and virtual devices
+    pub pci_slot: Option<String>,
+    pub vendor_id: Option<u32>,
+    pub device_id: Option<u32>,
+    // Kernel driver bound to the device, e.g. `amdgpu` or `virtio_gpu`
+    pub driver: Option<String>,
+    pub connectors: Vec<DrmConnector>,
+}
+
+impl DrmCard {
+    /// Connectors with a display plugged in
+    pub fn connected(&self) -> impl Iterator<Item = &DrmConnector> {
+        self.connectors.iter().filter(|c| {
+            fs::read_to_string(c.path.join("status")).is_ok_and(|s| s.trim() == "connected")
+        })
+    }
+}
+
+/// Reads a sysfs hex id like `0x1002`
+pub fn read_hex_id(path: &Path) -> Option<u32> {
+    let text = fs::read_to_string(path).ok()?;
+    u32::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Every DRM card in sysfs along with its connectors, sorted by card number
+pub fn get_drm_cards() -> Vec<DrmCard> {
+    let card_pattern = Regex::new(r"^card(\d+)$").unwrap();
+    let output_pattern = Regex::new(r"(?i)^(card\d+)-([A-Z0-9_-]+?-\d+)$").unwrap();
+
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return vec![];
+    };
+    let names: Vec<String> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+
+    let mut cards: Vec<(u32, DrmCard)> = vec![];
+    for name in names.iter() {
+        let Some(caps) = card_pattern.captures(name) else {
+            continue;
+        };
+        let num: u32 = caps[1].parse().unwrap_or(0);
+
+        let device_path = Path::new("/sys/class/drm").join(name).join("device");
+        // The device symlink points at e.g. `../../../0000:03:00.0`
+        let pci_slot = fs::canonicalize(&device_path)
+            .ok()
+            .filter(|p| p.join("vendor").is_file() && fs::read_link(p.join("subsystem")).is_ok_and(|s| s.ends_with("pci")))
+            .and_then(|p| p.file_name().map(|f| f.to_string_lossy().to_string()));
+
+        cards.push((
+            num,
+            DrmCard {
+                name: name.clone(),
+                vendor_id: read_hex_id(&device_path.join("vendor")),
+                device_id: read_hex_id(&device_path.join("device")),
+                driver: fs::read_link(device_path.join("driver"))
+                    .ok()
+                    .and_then(|p| p.file_name().map(|f| f.to_string_lossy().to_string())),
+                pci_slot,
+                connectors: vec![],
+            },
+        ));
+    }
+
+    for name in names.iter() {
+        let Some(caps) = output_pattern.captures(name) else {
+            continue;
+        };
+        if let Some((_, card)) = cards.iter_mut().find(|(_, c)| c.name == caps[1]) {
+            card.connectors.push(DrmConnector {
+                name: caps[2].to_string(),
+                path: Path::new("/sys/class/drm").join(name),
+            });
+        }
+    }
+
+    cards.sort_by_key(|(num, _)| *num);
+    cards
+        .into_iter()
+        .map(|(_, mut card)| {
+            card.connectors.sort_by(|a, b| a.name.cmp(&b.name));
+            card
+        })
+        .collect()
+}
@@ -0,0 +1,136 @@
+// Graphics API and userspace driver info from wgpu. Bringing up an instance is slow and can wake sleeping dGPUs,
+// so this only adds detail on top of what sysfs already found
+use std::collections::HashMap;
+
+use regex::Regex;
+use wgpu::Adapter;
+
+pub struct AdapterCnt {
+    count: i64,
+    pub name: String,
+    drivers: Vec<String>,
+    vulkan: bool,
+    gl: bool,
+    unrecognized_drivers: i64,
+    // PCI ids, used to match the adapter to its PCI device
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub device_type: wgpu::DeviceType,
+}
+
+impl AdapterCnt {
+    /// `Mesa 24.2.7, Vulkan/OpenGL`, `NVIDIA 550.54.14, Vulkan`, `1 unrecognized driver`
+    pub fn describe(&self) -> Vec<String> {
+        let mut parts: Vec<String> = vec![];
+
+        let mut drivers = self.drivers.clone();
+        drivers.sort();
+        drivers.dedup();
+        parts.extend(drivers);
+
+        match self.unrecognized_drivers {
+            0 => {}
+            1 => parts.push("1 unrecognized driver".to_string()),
+            n => parts.push(format!("{} unrecognized drivers", n)),
+        }
+
+        if self.gl && self.vulkan {
+            parts.push("Vulkan/OpenGL".to_string());
+        } else if self.gl {
+            parts.push("OpenGL".to_string());
+        } else if self.vulkan {
+            parts.push("Vulkan".to_string());
+        }
+
+        parts
+    }
+}
+
+/// Userspace driver and version from wgpu's `driver`/`driver_info`. Vulkan reports e.g. `radv` and
+/// `Mesa 24.2.7-arch1.1`, GL puts everything in `driver_info` like `4.6 (Core Profile) Mesa 24.2.7`
+pub fn recognize_driver(driver: &str, driver_info: &str) -> Option<String> {
+    let version = Regex::new(r"\d+\.(\d+|Q\d)(\.\d+)*").unwrap();
+    let version_after = |marker: &str| {
+        let idx = driver_info.find(marker)? + marker.len();
+        version.find(&driver_info[idx..]).map(|m| m.as_str().to_string())
+    };
+
+    if let Some(mesa) = version_after("Mesa") {
+        Some(format!("Mesa {}", mesa))
+    } else if driver.contains("AMD open-source driver") {
+        version.find(driver_info).map(|v| format!("AMDVLK {}", v.as_str()))
+    } else if driver.contains("AMD proprietary driver") {
+        version.find(driver_info).map(|v| format!("AMDGPU-PRO {}", v.as_str()))
+    } else if driver == "NVIDIA" {
+        version.find(driver_info).map(|v| format!("NVIDIA {}", v.as_str()))
+    } else {
+        version_after("NVIDIA").map(|v| format!("NVIDIA {}", v))
+    }
+}
+
+pub fn get_adapters() -> Vec<AdapterCnt> {
+    let instance = wgpu::Instance::default();
+    let mut gpu_counter: HashMap<String, AdapterCnt> = HashMap::new();
+
+
+    for a in instance.enumerate_adapters(wgpu::Backends::all()).iter().map(Adapter::get_info) {
+        let vulkan = Regex::new("(?i)vulkan").unwrap();
+        let gl = Regex::new("(?i)gl").unwrap();
+        let name_fixer = Regex::new(r"(?i)\(.*(LLVM|DRM|RADV).*\)").unwrap();
+
+        let name = name_fixer.replace(a.name.as_str(), "").trim().to_string();
+
+        let driver = recognize_driver(a.driver.as_str(), a.driver_info.as_str());
+
+        if gpu_counter.contains_key(&name) {
+            gpu_counter.get_mut(&name).unwrap().count += 1;
+            gpu_counter.get_mut(&name).unwrap().gl |= gl.is_match(a.backend.to_str());
+            gpu_counter.get_mut(&name).unwrap().vulkan |= vulkan.is_match(a.backend.to_str());
+            if let Some(driver) = driver {
+                gpu_counter.get_mut(&name).unwrap().drivers.push(driver);
+            } else {
+                gpu_counter.get_mut(&name).unwrap().unrecognized_drivers += 1;
+            }
+        } else {
+            gpu_counter.insert(
+                name.to_string(),
+                AdapterCnt {
+                    count: 1,
+                    name: name.to_string(),
+                    drivers: if driver.is_some() {
+                        vec![driver.clone().unwrap()]
+                    } else {
+                        vec![]
+                    },
+                    unrecognized_drivers: if driver.is_some() {
+                        0
+                    } else {
+                        1
+                    },
+                    vulkan: vulkan.is_match(a.backend.to_str()),
+                    gl: gl.is_match(a.backend.to_str()),
+                    vendor_id: a.vendor,
+                    device_id: a.device,
+                    device_type: a.device_type
+                },
+            );
+        }
+    }
+
+    gpu_counter.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn userspace_drivers() {
+        assert_eq!(recognize_driver("radv", "Mesa 24.2.7-arch1.1").as_deref(), Some("Mesa 24.2.7"));
+        assert_eq!(recognize_driver("", "4.6 (Core Profile) Mesa 24.2.7").as_deref(), Some("Mesa 24.2.7"));
+        assert_eq!(recognize_driver("AMD open-source driver", "2024.Q3.1 (LLPC)").as_deref(), Some("AMDVLK 2024.Q3.1"));
+        assert_eq!(recognize_driver("NVIDIA", "550.54.14").as_deref(), Some("NVIDIA 550.54.14"));
+        assert_eq!(recognize_driver("", "4.6.0 NVIDIA 550.54.14").as_deref(), Some("NVIDIA 550.54.14"));
+        assert_eq!(recognize_driver("mystery", ""), None);
+    }
+}
@@ -0,0 +1,473 @@
+#[cfg(feature = "wgpu")]
+mod adapters;
+mod pci_ids;
+mod stats;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use regex::Regex;
+
+#[cfg(feature = "wgpu")]
+use adapters::{get_adapters, AdapterCnt};
+use pci_ids::{short_name, short_vendor, PciIds};
+use stats::get_stats;
+
+use crate::{
+    conf::Config,
+    drm::{read_hex_id, DrmCard},
+    packages::get_package_version,
+    utils::format_tree,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuKind {
+    Integrated,
+    Discrete,
+    Virtual,
+    // Software renderers like llvmpipe
+    Cpu,
+    Unknown,
+}
+
+// QEMU/bochs, virtio, Red Hat (QXL), VMware, VirtualBox and Hyper-V
+const VIRTUAL_VENDORS: [u32; 6] = [0x1234, 0x1af4, 0x1b36, 0x15ad, 0x80ee, 0x1414];
+
+pub struct Gpu {
+    pub name: String,
+    pub vendor_id: Option<u32>,
+    pub device_id: Option<u32>,
+    pub kind: GpuKind,
+    // PCI address like `0000:03:00.0`
+    pub pci_slot: Option<String>,
+    // Firmware picked this one for the boot console, which is the primary display GPU
+    pub boot_vga: bool,
+    // Whoever built the board when it isn't the chip vendor, e.g. Sapphire or the laptop OEM
+    pub board: Option<String>,
+    // Kernel driver from the `driver` symlink
+    pub driver: Option<String>,
+    // DRM card driving it, e.g. `card1`
+    pub card: Option<String>,
+    // sysfs device directory, None for anything only wgpu knows about
+    pub path: Option<PathBuf>,
+    #[cfg(feature = "wgpu")]
+    pub adapter: Option<AdapterCnt>,
+}
+
+// `AMD Radeon RX 7900 XT/7900 XTX/7900M`, falls back to the raw ids for devices newer than the table
+fn pci_name(ids: &PciIds, vendor: u32, device: u32) -> String {
+    match ids.lookup(vendor, Some(device)) {
+        (Some(vendor_name), Some(device_name)) => {
+            format!("{} {}", short_vendor(vendor, &vendor_name), short_name(&device_name))
+        }
+        (Some(vendor_name), None) => format!("{} device {:04x}", short_vendor(vendor, &vendor_name), device),
+        _ => format!("{:04x}:{:04x}", vendor, device),
+    }
+}
+
+const MIB: u64 = 1024 * 1024;
+
+// Best guess without wgpu. Intel iGPUs always sit on bus 0, Arc cards never do. AMD APUs only have the VRAM
+// carve-out the firmware reserves for them, 512MiB by default and rarely over 2GiB, and drive the boot console
+fn guess_kind(vendor: u32, slot: &str, boot_vga: bool, vram_total: Option<u64>) -> GpuKind {
+    if VIRTUAL_VENDORS.contains(&vendor) {
+        return GpuKind::Virtual;
+    }
+    match (vendor, vram_total) {
+        (0x8086, _) if slot.get(5..7) == Some("00") => GpuKind::Integrated,
+        (0x8086 | 0x10de, _) => GpuKind::Discrete,
+        (0x1002, Some(vram)) if vram <= 512 * MIB || (boot_vga && vram < 2048 * MIB) => GpuKind::Integrated,
+        (0x1002, Some(_)) => GpuKind::Discrete,
+        _ => GpuKind::Unknown,
+    }
+}
+
+// Display controllers (PCI class 0x03) on the PCI bus, plus DRM cards that aren't on it (ARM SoCs, virtio-mmio)
+fn get_sysfs_gpus(cards: &[DrmCard]) -> Vec<Gpu> {
+    let mut gpus: Vec<Gpu> = vec![];
+    let ids = PciIds::load();
+
+    let mut slots: Vec<String> = fs::read_dir("/sys/bus/pci/devices")
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    slots.sort();
+
+    for slot in slots {
+        let path = Path::new("/sys/bus/pci/devices").join(&slot);
+        let is_display = fs::read_to_string(path.join("class")).is_ok_and(|c| c.trim().starts_with("0x03"));
+        if !is_display {
+            continue;
+        }
+        let (Some(vendor), Some(device_id)) = (read_hex_id(&path.join("vendor")), read_hex_id(&path.join("device"))) else {
+            continue;
+        };
+
+        let board = read_hex_id(&path.join("subsystem_vendor"))
+            .filter(|v| *v != vendor && *v != 0)
+            .and_then(|v| ids.lookup(v, None).0.map(|name| short_vendor(v, &name)));
+
+        // virtio-gpu hangs its DRM card off a virtio device below the PCI one, so the card has no PCI slot
+        // and the PCI driver is just the `virtio-pci` transport
+        let device = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        let card = cards.iter().find(|c| {
+            c.pci_slot.as_deref() == Some(slot.as_str())
+                || fs::canonicalize(Path::new("/sys/class/drm").join(&c.name).join("device"))
+                    .is_ok_and(|p| p.starts_with(&device))
+        });
+        let pci_driver = fs::read_link(path.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|f| f.to_string_lossy().to_string()));
+
+        let boot_vga = fs::read_to_string(path.join("boot_vga")).is_ok_and(|v| v.trim() == "1");
+        // Only amdgpu has this
+        let vram_total = fs::read_to_string(path.join("mem_info_vram_total")).ok().and_then(|v| v.trim().parse().ok());
+
+        gpus.push(Gpu {
+            name: pci_name(&ids, vendor, device_id),
+            vendor_id: Some(vendor),
+            device_id: Some(device_id),
+            kind: guess_kind(vendor, &slot, boot_vga, vram_total),
+            boot_vga,
+            pci_slot: Some(slot.clone()),
+            board,
+            driver: card.and_then(|c| c.driver.clone()).or(pci_driver),
+            card: card.map(|c| c.name.clone()),
+            path: Some(path),
+            #[cfg(feature = "wgpu")]
+            adapter: None,
+        });
+    }
+
+    // simpledrm is just the firmware framebuffer wearing a DRM card
+    let unclaimed: Vec<&DrmCard> = cards
+        .iter()
+        .filter(|c| !gpus.iter().any(|g| g.card.as_ref() == Some(&c.name)))
+        .filter(|c| !c.driver.as_deref().is_some_and(|d| d.contains("framebuffer")))
+        .collect();
+    for card in unclaimed {
+        let kind = match card.driver.as_deref() {
+            Some("virtio_gpu" | "vkms") => GpuKind::Virtual,
+            // Anything else off the PCI bus is part of an SoC
+            _ => GpuKind::Integrated,
+        };
+        gpus.push(Gpu {
+            name: card.driver.clone().unwrap_or_else(|| card.name.clone()),
+            vendor_id: card.vendor_id,
+            device_id: card.device_id,
+            kind,
+            pci_slot: None,
+            boot_vga: false,
+            board: None,
+            driver: card.driver.clone(),
+            card: Some(card.name.clone()),
+            path: Some(Path::new("/sys/class/drm").join(&card.name).join("device")),
+            #[cfg(feature = "wgpu")]
+            adapter: None,
+        });
+    }
+
+    gpus
+}
+
+// `nvidia 550.54.14`, in-tree modules don't have a version of their own so they're just the module name
+fn kernel_driver(driver: &str) -> String {
+    let version = fs::read_to_string(Path::new("/sys/module").join(driver.replace('-', "_")).join("version"))
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| {
+            if driver != "nvidia" {
+                return None;
+            }
+            parse_nvidia_version(&fs::read_to_string("/proc/driver/nvidia/version").ok()?)
+        });
+
+    match version {
+        Some(version) => format!("{} {}", driver, version),
+        None => driver.to_string(),
+    }
+}
+
+/// `NVRM version: NVIDIA UNIX x86_64 Kernel Module  550.54.14  Thu Feb 22 01:44:30 UTC 2024`
+pub fn parse_nvidia_version(text: &str) -> Option<String> {
+    let line = text.lines().find(|l| l.starts_with("NVRM"))?;
+    let pattern = Regex::new(r"Kernel Module.*?\s(\d+\.\d+(\.\d+)?)").unwrap();
+    pattern.captures(line).map(|c| c[1].to_string())
+}
+
+// Kernel drivers whose userspace side is Mesa
+const MESA_DRIVERS: [&str; 15] = [
+    "amdgpu", "radeon", "i915", "xe", "nouveau", "virtio_gpu", "vmwgfx", "msm", "panfrost", "lima", "v3d", "vc4",
+    "etnaviv", "asahi", "powervr",
+];
+
+// `Mesa 24.2.7` or `NVIDIA 550.54.14` from the package manager, what wgpu would have told us if it were compiled in.
+// The NVIDIA userspace has to match its kernel module, so that version is the fallback
+fn userspace_driver(driver: &str) -> Option<String> {
+    if MESA_DRIVERS.contains(&driver) {
+        let version = ["mesa", "libgl1-mesa-dri", "mesa-vulkan-drivers"].iter().find_map(|p| get_package_version(p))?;
+        return Some(format!("Mesa {}", version));
+    }
+    if driver == "nvidia" {
+        let version = get_package_version("nvidia-utils").or_else(|| {
+            parse_nvidia_version(&fs::read_to_string("/proc/driver/nvidia/version").ok()?)
+        })?;
+        return Some(format!("NVIDIA {}", version));
+    }
+    None
+}
+
+// Graphics API and userspace driver from wgpu, None when it has nothing on this GPU
+#[cfg(feature = "wgpu")]
+fn adapter_details(gpu: &Gpu) -> Option<Vec<String>> {
+    gpu.adapter.as_ref().map(|a| a.describe())
+}
+
+#[cfg(not(feature = "wgpu"))]
+fn adapter_details(_: &Gpu) -> Option<Vec<String>> {
+    None
+}
+
+#[cfg(feature = "wgpu")]
+fn adapter_kind(device_type: wgpu::DeviceType) -> GpuKind {
+    match device_type {
+        wgpu::DeviceType::IntegratedGpu => GpuKind::Integrated,
+        wgpu::DeviceType::DiscreteGpu => GpuKind::Discrete,
+        wgpu::DeviceType::VirtualGpu => GpuKind::Virtual,
+        wgpu::DeviceType::Cpu => GpuKind::Cpu,
+        wgpu::DeviceType::Other => GpuKind::Unknown,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PrimeTarget {
+    // `DRI_PRIME=1`, whichever GPU isn't the default
+    NonDefault,
+    // `DRI_PRIME=pci-0000_03_00_0`
+    Slot(String),
+    // `DRI_PRIME=1002:744c`
+    Ids(u32, u32),
+}
+
+/// Parses Mesa's `DRI_PRIME`, None when it's unset or `0`
+pub fn parse_dri_prime(value: &str) -> Option<PrimeTarget> {
+    let value = value.trim();
+    if let Some(slot) = value.strip_prefix("pci-") {
+        // `0000_03_00_0` -> `0000:03:00.0`
+        let parts: Vec<&str> = slot.split('_').collect();
+        if let [domain, bus, device, function] = parts.as_slice() {
+            return Some(PrimeTarget::Slot(format!("{}:{}:{}.{}", domain, bus, device, function)));
+        }
+        return None;
+    }
+    if let Some((vendor, device)) = value.split_once(':') {
+        return Some(PrimeTarget::Ids(
+            u32::from_str_radix(vendor, 16).ok()?,
+            u32::from_str_radix(device, 16).ok()?,
+        ));
+    }
+    match value.parse::<u32>() {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(PrimeTarget::NonDefault),
+    }
+}
+
+// Index of the GPU render offloading is pointed at, from Mesa's `DRI_PRIME` or NVIDIA's
+// `__NV_PRIME_RENDER_OFFLOAD`
+fn get_prime_offload(gpus: &[&Gpu]) -> Option<usize> {
+    if env::var("__NV_PRIME_RENDER_OFFLOAD").is_ok_and(|v| v == "1") {
+        return gpus.iter().position(|g| g.vendor_id == Some(0x10de));
+    }
+
+    match parse_dri_prime(&env::var("DRI_PRIME").ok()?)? {
+        PrimeTarget::NonDefault => gpus.iter().position(|g| !g.boot_vga && g.pci_slot.is_some()),
+        PrimeTarget::Slot(slot) => gpus.iter().position(|g| g.pci_slot.as_ref() == Some(&slot)),
+        PrimeTarget::Ids(vendor, device) => {
+            gpus.iter().position(|g| g.vendor_id == Some(vendor) && g.device_id == Some(device))
+        }
+    }
+}
+
+fn is_software(gpu: &Gpu) -> bool {
+    static SOFTWARE: OnceLock<Regex> = OnceLock::new();
+    let software = SOFTWARE.get_or_init(|| Regex::new(r"(?i)llvmpipe|lavapipe|softpipe|swiftshader").unwrap());
+    gpu.kind == GpuKind::Cpu || software.is_match(&gpu.name)
+}
+
+/// Every GPU sysfs knows about, with wgpu filling in API details and anything sysfs can't see (software
+/// renderers, containers without /sys) when compiled in
+pub fn get_gpu_list(cards: &[DrmCard]) -> Vec<Gpu> {
+    let gpus = get_sysfs_gpus(cards);
+
+    #[cfg(feature = "wgpu")]
+    let gpus = {
+        let mut gpus = gpus;
+        for adapter in get_adapters() {
+            let matching = gpus.iter_mut().find(|g| {
+                g.adapter.is_none() && g.vendor_id == Some(adapter.vendor_id) && g.device_id == Some(adapter.device_id)
+            });
+            match matching {
+                // The driver's marketing name beats a pci.ids entry covering three cards
+                Some(gpu) => {
+                    gpu.name = adapter.name.clone();
+                    if adapter.device_type != wgpu::DeviceType::Other {
+                        gpu.kind = adapter_kind(adapter.device_type);
+                    }
+                    gpu.adapter = Some(adapter);
+                }
+                None => gpus.push(Gpu {
+                    name: adapter.name.clone(),
+                    vendor_id: None,
+                    device_id: None,
+                    kind: adapter_kind(adapter.device_type),
+                    pci_slot: None,
+                    boot_vga: false,
+                    board: None,
+                    driver: None,
+                    card: None,
+                    path: None,
+                    adapter: Some(adapter),
+                }),
+            }
+        }
+        gpus
+    };
+
+    gpus
+}
+
+/// One tree entry per GPU with its live stats underneath, and how many GPUs were listed for the label
+pub fn get_gpus(gpus: &[Gpu], cards: &[DrmCard], config: &Config) -> (String, usize) {
+    let mut out: Vec<String> = vec![];
+    // Only worth pointing at the card and its outputs when there is more than one to tell apart
+    let multi_gpu = cards.len() > 1;
+
+    // CPU renderers aren't GPUs, they're only interesting when asked for
+    let all = gpus.len();
+    let gpus: Vec<&Gpu> = gpus.iter().filter(|g| config.gpu_software || !is_software(g)).collect();
+    let prime = get_prime_offload(&gpus);
+    let hardware = gpus.iter().filter(|g| g.pci_slot.is_some()).count();
+
+    for (i, gpu) in gpus.iter().enumerate() {
+        let mut parts: Vec<String> = vec![];
+        match gpu.kind {
+            GpuKind::Integrated => parts.push("integrated".to_string()),
+            GpuKind::Discrete => parts.push("discrete".to_string()),
+            GpuKind::Virtual => parts.push("virtual".to_string()),
+            GpuKind::Cpu => parts.push("software".to_string()),
+            GpuKind::Unknown => {}
+        }
+        if gpu.boot_vga && hardware > 1 {
+            parts.push("primary".to_string());
+        }
+        if prime == Some(i) {
+            parts.push("PRIME offload".to_string());
+        }
+        if let Some(board) = &gpu.board {
+            parts.push(board.clone());
+        }
+        if let Some(driver) = &gpu.driver {
+            parts.push(kernel_driver(driver));
+        }
+        match adapter_details(gpu) {
+            Some(details) => parts.extend(details),
+            None => parts.extend(gpu.driver.as_deref().and_then(userspace_driver)),
+        }
+
+        let mut line = gpu.name.clone();
+        if !parts.is_empty() {
+            line += format!(" ({})", parts.join(", ")).as_str();
+        }
+
+        let card = cards.iter().find(|c| Some(&c.name) == gpu.card.as_ref());
+        if let Some(card) = card.filter(|_| multi_gpu) {
+            let outputs: Vec<&str> = card.connected().map(|c| c.name.as_str()).collect();
+            if outputs.is_empty() {
+                line += format!(" [{}]", card.name).as_str();
+            } else {
+                line += format!(" [{}: {}]", card.name, outputs.join(", ")).as_str();
+            }
+        }
+
+        let stats = gpu.path.as_ref().and_then(|p| get_stats(p, gpu.card.as_deref()));
+        match stats {
+            Some(stats) => out.push(line + "\n" + format_tree(&[stats.describe(&config.units)]).as_str()),
+            None => out.push(line),
+        }
+    }
+
+    if out.is_empty() && all > 0 {
+        return ("None (software rendering only)".to_string(), 0);
+    } else if out.is_empty() {
+        return ("None... found?".to_string(), 0);
+    } else {
+        return (format_tree(&out), out.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dri_prime() {
+        assert_eq!(parse_dri_prime("1"), Some(PrimeTarget::NonDefault));
+        assert_eq!(parse_dri_prime("0"), None);
+        assert_eq!(parse_dri_prime("pci-0000_03_00_0"), Some(PrimeTarget::Slot("0000:03:00.0".to_string())));
+        assert_eq!(parse_dri_prime("1002:744c"), Some(PrimeTarget::Ids(0x1002, 0x744c)));
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(guess_kind(0x8086, "0000:00:02.0", true, None), GpuKind::Integrated);
+        assert_eq!(guess_kind(0x8086, "0000:03:00.0", false, None), GpuKind::Discrete);
+        // Radeon 780M with the default carve-out next to an RX 7900 XT
+        assert_eq!(guess_kind(0x1002, "0000:c5:00.0", true, Some(512 * MIB)), GpuKind::Integrated);
+        assert_eq!(guess_kind(0x1002, "0000:03:00.0", false, Some(20464 * MIB)), GpuKind::Discrete);
+        // Steam Deck style 1GiB carve-out, and a desktop where the dGPU drives the console
+        assert_eq!(guess_kind(0x1002, "0000:04:00.0", true, Some(1024 * MIB)), GpuKind::Integrated);
+        assert_eq!(guess_kind(0x1002, "0000:03:00.0", true, Some(8176 * MIB)), GpuKind::Discrete);
+        // radeon doesn't report VRAM
+        assert_eq!(guess_kind(0x1002, "0000:01:00.0", true, None), GpuKind::Unknown);
+        assert_eq!(guess_kind(0x1af4, "0000:00:01.0", true, None), GpuKind::Virtual);
+    }
+
+    #[test]
+    fn nvidia_version() {
+        let text = "NVRM version: NVIDIA UNIX x86_64 Kernel Module  550.54.14  Thu Feb 22 01:44:30 UTC 2024\n\
+                    GCC version:  gcc version 13.2.1 20230801 (GCC)\n";
+        assert_eq!(parse_nvidia_version(text).as_deref(), Some("550.54.14"));
+        assert_eq!(parse_nvidia_version("GCC version:  gcc version 13.2.1\n"), None);
+    }
+
+    #[test]
+    fn single_gpu_with_stats() {
+        let device = env::temp_dir().join(format!("yatfpbnws-gpu-{}", std::process::id()));
+        fs::create_dir_all(&device).unwrap();
+        fs::write(device.join("gpu_busy_percent"), "3\n").unwrap();
+
+        let gpu = Gpu {
+            name: "AMD Radeon RX 7900 XT".to_string(),
+            vendor_id: Some(0x1002),
+            device_id: Some(0x744c),
+            kind: GpuKind::Discrete,
+            pci_slot: Some("0000:03:00.0".to_string()),
+            boot_vga: true,
+            board: None,
+            driver: None,
+            card: None,
+            path: Some(device.clone()),
+            #[cfg(feature = "wgpu")]
+            adapter: None,
+        };
+        let (out, count) = get_gpus(&[gpu], &[], &Config::default());
+        fs::remove_dir_all(&device).unwrap();
+
+        // The stats sub-line doesn't make it a second GPU
+        assert_eq!(out.lines().count(), 2);
+        assert_eq!(count, 1);
+    }
+}
@@ -0,0 +1,128 @@
+// PCI vendor and device names. The system's pci.ids is preferred since distros keep it current, the bundled
+// copy (data/pci.ids.deflate, see data/pci_ids.py) covers minimal installs and containers
+use std::fs;
+
+static BUNDLED: &[u8] = include_bytes!("../../data/pci.ids.deflate");
+
+const SYSTEM_PATHS: [&str; 3] = ["/usr/share/hwdata/pci.ids", "/usr/share/misc/pci.ids", "/usr/share/pci.ids"];
+
+pub struct PciIds {
+    text: String,
+}
+
+impl PciIds {
+    pub fn load() -> PciIds {
+        for path in SYSTEM_PATHS {
+            if let Ok(text) = fs::read_to_string(path) {
+                return PciIds { text };
+            }
+        }
+        let text = miniz_oxide::inflate::decompress_to_vec(BUNDLED)
+            .map(|data| String::from_utf8_lossy(&data).to_string())
+            .unwrap_or_default();
+        PciIds { text }
+    }
+
+    /// (vendor name, device name), device is only looked up when given
+    pub fn lookup(&self, vendor: u32, device: Option<u32>) -> (Option<String>, Option<String>) {
+        lookup(&self.text, vendor, device)
+    }
+}
+
+// Vendors sit at the start of a line, their devices are indented by one tab and subsystems by two:
+// `1002  Advanced Micro Devices, Inc. [AMD/ATI]` then `\t744c  Navi 31 [Radeon RX 7900 XT/7900 XTX/7900M]`
+fn lookup(text: &str, vendor: u32, device: Option<u32>) -> (Option<String>, Option<String>) {
+    let vendor_id = format!("{:04x}", vendor);
+    let device_id = device.map(|d| format!("{:04x}", d));
+    let mut vendor_name: Option<String> = None;
+
+    for line in text.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        // Device classes come after every vendor
+        if line.starts_with("C ") {
+            break;
+        }
+
+        if !line.starts_with('\t') {
+            if vendor_name.is_some() {
+                break;
+            }
+            if let Some(name) = line.strip_prefix(vendor_id.as_str()) {
+                vendor_name = Some(name.trim().to_string());
+                if device_id.is_none() {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        if let (Some(_), Some(device_id)) = (&vendor_name, &device_id) {
+            if let Some(name) = line.strip_prefix('\t').and_then(|l| l.strip_prefix(device_id.as_str())) {
+                return (vendor_name, Some(name.trim().to_string()));
+            }
+        }
+    }
+
+    (vendor_name, None)
+}
+
+/// Marketing name out of a pci.ids entry, `Navi 31 [Radeon RX 7900 XT/7900 XTX/7900M]` has it in brackets
+pub fn short_name(name: &str) -> String {
+    match (name.find('['), name.rfind(']')) {
+        (Some(start), Some(end)) if start < end => name[start + 1..end].to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// What vendors go by, e.g. `NVIDIA` for `NVIDIA Corporation` or `MSI` for `Micro-Star International Co., Ltd. [MSI]`
+pub fn short_vendor(vendor: u32, name: &str) -> String {
+    match vendor {
+        0x1002 => "AMD".to_string(),
+        0x10de => "NVIDIA".to_string(),
+        0x8086 => "Intel".to_string(),
+        _ if name.contains('[') => short_name(name),
+        _ => name
+            .split(',')
+            .next()
+            .unwrap_or(name)
+            .trim_end_matches(" Corporation")
+            .trim_end_matches(" Inc.")
+            .trim_end_matches(" Ltd.")
+            .trim()
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# comment
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t744c  Navi 31 [Radeon RX 7900 XT/7900 XTX/7900M]
+\t\t1002 0e3b  Radeon RX 7900 GRE
+10de  NVIDIA Corporation
+\t2684  AD102 [GeForce RTX 4090]
+1043  ASUSTeK Computer Inc.
+C 03  Display controller
+";
+
+    #[test]
+    fn lookups() {
+        assert_eq!(
+            lookup(SAMPLE, 0x10de, Some(0x2684)),
+            (Some("NVIDIA Corporation".to_string()), Some("AD102 [GeForce RTX 4090]".to_string()))
+        );
+        assert_eq!(lookup(SAMPLE, 0x1002, Some(0x1234)).1, None);
+        assert_eq!(short_vendor(0x1043, &lookup(SAMPLE, 0x1043, None).0.unwrap()), "ASUSTeK Computer");
+        assert_eq!(short_name("Navi 31 [Radeon RX 7900 XT/7900 XTX/7900M]"), "Radeon RX 7900 XT/7900 XTX/7900M");
+    }
+
+    #[test]
+    fn bundled_table() {
+        let ids = PciIds { text: String::from_utf8(miniz_oxide::inflate::decompress_to_vec(BUNDLED).unwrap()).unwrap() };
+        assert_eq!(ids.lookup(0x1002, None).0.as_deref(), Some("Advanced Micro Devices, Inc. [AMD/ATI]"));
+    }
+}
@@ -0,0 +1,166 @@
+// Live GPU stats from the kernel driver. amdgpu exposes the most, i915/xe have clocks and nouveau only has
+// hwmon, anything a driver doesn't expose is left out
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::units::Units;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct GpuStats {
+    // Runtime suspended dGPUs are left asleep, reading most of these would wake them
+    pub suspended: bool,
+    // (used, total) in bytes
+    pub vram: Option<(u64, u64)>,
+    pub busy_percent: Option<u32>,
+    // (current, max) in MHz
+    pub core_clock: Option<(u32, u32)>,
+    pub memory_clock: Option<(u32, u32)>,
+    pub temperature: Option<f32>,
+    pub fan_rpm: Option<u32>,
+    pub power_watts: Option<f32>,
+}
+
+fn read_number<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Parses amdgpu `pp_dpm_sclk`/`pp_dpm_mclk`, one `N: 1800Mhz` line per level with the current one starred
+pub fn parse_dpm(text: &str) -> Option<(u32, u32)> {
+    let mut current = None;
+    let mut max = None;
+    for line in text.lines() {
+        let Some((_, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let mhz: Option<u32> = rest
+            .trim()
+            .trim_end_matches('*')
+            .trim()
+            .to_lowercase()
+            .strip_suffix("mhz")
+            .and_then(|n| n.trim().parse().ok());
+        let Some(mhz) = mhz else {
+            continue;
+        };
+        if line.trim_end().ends_with('*') {
+            current = Some(mhz);
+        }
+        max = max.max(Some(mhz));
+    }
+    Some((current?, max?))
+}
+
+fn get_hwmon(device: &Path) -> Option<PathBuf> {
+    fs::read_dir(device.join("hwmon")).ok()?.flatten().map(|e| e.path()).next()
+}
+
+// i915 puts its clocks on the card, xe on every GT of the device
+fn intel_clock(device: &Path, card: Option<&str>) -> Option<(u32, u32)> {
+    if let Some(card) = card {
+        let card = Path::new("/sys/class/drm").join(card);
+        if let (Some(cur), Some(max)) = (
+            read_number(&card.join("gt_act_freq_mhz")).or_else(|| read_number(&card.join("gt_cur_freq_mhz"))),
+            read_number(&card.join("gt_RP0_freq_mhz")),
+        ) {
+            return Some((cur, max));
+        }
+    }
+
+    let freq = device.join("tile0/gt0/freq0");
+    Some((read_number(&freq.join("act_freq"))?, read_number(&freq.join("rp0_freq"))?))
+}
+
+/// Stats for the GPU at `device` (its sysfs device directory), None when the driver has nothing to offer
+pub fn get_stats(device: &Path, card: Option<&str>) -> Option<GpuStats> {
+    if fs::read_to_string(device.join("power/runtime_status")).is_ok_and(|s| s.trim() == "suspended") {
+        return Some(GpuStats { suspended: true, ..Default::default() });
+    }
+
+    let mut stats = GpuStats {
+        busy_percent: read_number(&device.join("gpu_busy_percent")),
+        core_clock: fs::read_to_string(device.join("pp_dpm_sclk")).ok().and_then(|t| parse_dpm(&t)),
+        memory_clock: fs::read_to_string(device.join("pp_dpm_mclk")).ok().and_then(|t| parse_dpm(&t)),
+        ..Default::default()
+    };
+    if let (Some(used), Some(total)) = (
+        read_number(&device.join("mem_info_vram_used")),
+        read_number(&device.join("mem_info_vram_total")),
+    ) {
+        stats.vram = Some((used, total));
+    }
+    if stats.core_clock.is_none() {
+        stats.core_clock = intel_clock(device, card);
+    }
+
+    if let Some(hwmon) = get_hwmon(device) {
+        // Millidegrees, RPM and microwatts
+        stats.temperature = read_number::<f32>(&hwmon.join("temp1_input")).map(|t| t / 1000.0);
+        stats.fan_rpm = read_number(&hwmon.join("fan1_input"));
+        stats.power_watts = read_number::<f32>(&hwmon.join("power1_average"))
+            .or_else(|| read_number(&hwmon.join("power1_input")))
+            .map(|p| p / 1_000_000.0);
+    }
+
+    if stats == GpuStats::default() {
+        None
+    } else {
+        Some(stats)
+    }
+}
+
+impl GpuStats {
+    /// `VRAM 2.1/24.0GiB, 3% busy, 43°C, 0 RPM, 15W, core 500/2500MHz, mem 96/1250MHz`
+    pub fn describe(&self, units: &Units) -> String {
+        if self.suspended {
+            return "Suspended".to_string();
+        }
+
+        let mut parts: Vec<String> = vec![];
+        if let Some((used, total)) = self.vram {
+            parts.push(format!("VRAM {}", units.bytes_pair(used, total)));
+        }
+        if let Some(busy) = self.busy_percent {
+            parts.push(format!("{}% busy", busy));
+        }
+        if let Some(temperature) = self.temperature {
+            parts.push(units.temperature(temperature));
+        }
+        if let Some(rpm) = self.fan_rpm {
+            parts.push(format!("{} RPM", rpm));
+        }
+        if let Some(watts) = self.power_watts {
+            parts.push(format!("{:.0}W", watts));
+        }
+        if let Some((current, max)) = self.core_clock {
+            parts.push(format!("core {}", units.clock_pair(current as f64, max as f64)));
+        }
+        if let Some((current, max)) = self.memory_clock {
+            parts.push(format!("mem {}", units.clock_pair(current as f64, max as f64)));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dpm_levels() {
+        assert_eq!(parse_dpm("0: 500Mhz\n1: 1800Mhz *\n2: 2500Mhz\n"), Some((1800, 2500)));
+        // Deep sleep shows up as its own level
+        assert_eq!(parse_dpm("S: 19Mhz *\n0: 500Mhz\n1: 2482Mhz\n"), Some((19, 2482)));
+        assert_eq!(parse_dpm(""), None);
+    }
+
+    #[test]
+    fn clocks() {
+        let stats = GpuStats { core_clock: Some((500, 2500)), memory_clock: Some((96, 1250)), ..Default::default() };
+        assert_eq!(stats.describe(&Units::default()), "core 500/2500MHz, mem 96/1250MHz");
+
+        let suspended = GpuStats { suspended: true, ..stats };
+        assert_eq!(suspended.describe(&Units::default()), "Suspended");
+    }
+}
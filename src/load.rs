@@ -0,0 +1,62 @@
+use owo_colors::OwoColorize;
+use sysinfo::{ProcessStatus, System, ThreadKind};
+
+// Yellow once the run queue is most of the way to saturating every core, red once it's past that. SMT
+// siblings share a core's execution units so they don't count as extra headroom
+fn colorize(load: f64, cores: f64) -> String {
+    let text = format!("{:.2}", load);
+    if load > cores {
+        format!("{}", text.red())
+    } else if load > cores * 0.7 {
+        format!("{}", text.yellow())
+    } else {
+        text
+    }
+}
+
+/// `0.52, 0.61, 0.70 (0.03 per core), 312 processes, 1403 threads, 2 running`. Needs the CPU list refreshed
+/// beforehand, `get_cpus()` takes care of that
+pub fn get_load(sys: &System) -> String {
+    let load = System::load_average();
+    let cores = sys.physical_core_count().unwrap_or(sys.cpus().len()).max(1) as f64;
+
+    let mut processes = 0;
+    let mut threads = 0;
+    let mut running = 0;
+    // On Linux every thread shows up as its own entry, userland threads are the ones that aren't processes
+    for process in sys.processes().values() {
+        threads += 1;
+        if process.thread_kind() != Some(ThreadKind::Userland) {
+            processes += 1;
+        }
+        if process.status() == ProcessStatus::Run {
+            running += 1;
+        }
+    }
+
+    format!(
+        "{}, {}, {} ({:.2} per core), {} processes, {} threads, {} running",
+        colorize(load.one, cores),
+        colorize(load.five, cores),
+        colorize(load.fifteen, cores),
+        load.one / cores,
+        processes,
+        threads,
+        running
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds() {
+        // 4 cores: yellow above 2.8, red above 4
+        assert_eq!(colorize(0.5, 4.0), "0.50");
+        assert_eq!(colorize(2.8, 4.0), "2.80");
+        assert_eq!(colorize(3.0, 4.0), format!("{}", "3.00".yellow()));
+        assert_eq!(colorize(4.0, 4.0), format!("{}", "4.00".yellow()));
+        assert_eq!(colorize(4.5, 4.0), format!("{}", "4.50".red()));
+    }
+}
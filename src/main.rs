@@ -1,36 +1,59 @@
 #![feature(int_roundings)]
+// The codebase writes out `return x;` on purpose, keep clippy from flagging it
+#![allow(clippy::needless_return)]
 
+mod cache;
 mod conf;
 mod cpu;
 mod desktop;
+mod dimms;
+mod disks;
 mod displays;
+mod drm;
 mod gpus;
+mod load;
+mod memory;
 mod model;
 mod packages;
+mod session;
 mod shell;
 mod terminal;
+mod theme;
+mod units;
 mod uptime;
 mod utils;
 mod viuer;
+mod vulnerabilities;
+mod wm;
 
 use clap::{arg, command, ArgAction};
+use conf::get_config;
 use cpu::get_cpus;
 use crossterm::{cursor, execute};
 use csscolorparser::Color;
-use desktop::get_de;
+use desktop::{get_de, get_display_server};
+use dimms::get_dimms;
+use disks::get_disks;
 use displays::get_displays;
-use gpus::get_gpus;
+use drm::get_drm_cards;
+use gpus::{get_gpu_list, get_gpus};
 use image::{imageops, DynamicImage, ImageBuffer, Rgba};
+use load::get_load;
+use memory::{get_memory_details, get_swap_details};
 use model::get_model;
 use owo_colors::OwoColorize;
 use packages::get_packages;
+use session::{get_session, is_remote};
 use shell::get_shell;
 use std::fmt::Display;
 use std::{env, io};
 use sysinfo::{MemoryRefreshKind, Pid, ProcessRefreshKind, RefreshKind, System, Users};
 use terminal::get_term;
+use theme::get_themes;
 use text_splitter::TextSplitter;
 use uptime::get_uptime;
+use vulnerabilities::get_vulnerabilities;
+use wm::get_wm;
 
 macro_rules! clearScreen {
     ($T:expr) => {
@@ -90,17 +113,17 @@ macro_rules! moveCursorX {
     };
 }
 
-struct RGB {
+struct Rgb {
     r: u8,
     g: u8,
     b: u8
 }
 
-fn calc_truecolor(w: u16, h: u16, x: u16, y: u16) -> RGB {
+fn calc_truecolor(w: u16, h: u16, x: u16, y: u16) -> Rgb {
     let xf = x as f32 / w as f32;
     let yf = y as f32 / h as f32;
 
-    let mut out = RGB {
+    let mut out = Rgb {
         r: 0,
         g: 0,
         b: 0
@@ -142,14 +165,17 @@ fn main() {
         .arg(arg!(-w --colorwidth <WIDTH> "Width of the color blocks (default: 3, 0 to disable)").required(false).value_parser(clap::value_parser!(u16)))
         .arg(arg!(-t --truecolor "Enable truecolor block (will be a minimum of [colorwidth/2 * colorwidth/2])").action(ArgAction::SetTrue))
         .arg(arg!(-u --cpuusage "Enable cpu usage (requires an extra delay, may be slow)").action(ArgAction::SetTrue))
+        .arg(arg!(-d --displayinfo "Show monitor serial numbers and manufacture dates").action(ArgAction::SetTrue))
         .get_matches();
 
+    let config = get_config(args.get_one::<String>("conf"));
+
     let mut im_w =
         ((term_size_x as f32 / 2.0).floor() - (name_string.len() as f32 / 2.0).floor()) as u32;
     if im_w / 2 > (term_size_y - 2) as u32 {
         im_w = ((term_size_y - 2) * 2) as u32
     }
-    let mut im_h = 0 as u32;
+    let mut im_h = 0_u32;
     let im_path = args.get_one::<String>("im");
     let bg_color = args.get_one::<String>("bgc");
     let has_im = im_path.is_some();
@@ -193,23 +219,59 @@ fn main() {
     addLine!(lines, Some("Model".to_string()), format!("{}", get_model()));
 
     // Uptime
-    addLine!(lines, Some("Uptime".to_string()), get_uptime());
+    addLine!(lines, Some("Uptime".to_string()), get_uptime(&config.units));
 
     // Packages
     // https://github.com/dylanaraps/neofetch/blob/ccd5d9f52609bbdcd5d8fa78c4fdb0f12954125f/neofetch#L1509
     addLine!(lines, Some("Packages".to_string()), get_packages());
 
+    // Session, only shown when remote or on a bare tty
+    if let Some(session) = get_session(&sys) {
+        addLine!(lines, Some("Session".to_string()), session);
+    }
+
     // Terminal
     addLine!(lines, Some("Terminal".to_string()), get_term(&sys));
 
     // Shell
     addLine!(lines, Some("Shell".to_string()), get_shell(&sys));
 
-    // Desktop env
-    addLine!(lines, Some("DE".to_string()), get_de());
+    // Desktop env and window manager, skip the DE line if the WM is all there is
+    let de = get_de();
+    let wm = get_wm(&sys);
+    if de.is_some() || wm.is_none() {
+        addLine!(
+            lines,
+            Some("DE".to_string()),
+            de.unwrap_or_else(|| format!("Unknown ({})", get_display_server()))
+        );
+    }
+    if let Some(wm) = wm {
+        addLine!(lines, Some("WM".to_string()), wm);
+    }
+
+    // Theming, each line only shows up if something is configured
+    let themes = get_themes();
+    if let Some(theme) = themes.theme {
+        addLine!(lines, Some("Theme".to_string()), theme);
+    }
+    if let Some(icons) = themes.icons {
+        addLine!(lines, Some("Icons".to_string()), icons);
+    }
+    if let Some(cursor) = themes.cursor {
+        addLine!(lines, Some("Cursor".to_string()), cursor);
+    }
+    if let Some(font) = themes.font {
+        addLine!(lines, Some("Font".to_string()), font);
+    }
+
+    // GPUs are needed up front so displays can be grouped under the card driving them
+    let drm_cards = get_drm_cards();
+    let gpu_list = get_gpu_list(&drm_cards);
 
     // Displays
-    let disps = get_displays();
+    let randr = config.displays_randr && !is_remote(&sys);
+    let disps = get_displays(*args.get_one::<bool>("displayinfo").unwrap(), randr, &gpu_list, &drm_cards);
     let tmp = disps.split("\n");
     addLine!(lines, Some("Displays".to_string()), "".to_string());
     for i in tmp {
@@ -217,14 +279,14 @@ fn main() {
     }
 
     // CPUs
-    let cpus = get_cpus(&mut sys, args.get_one::<bool>("cpuusage").unwrap().clone());
+    let (cpus, cpu_count) = get_cpus(&mut sys, *args.get_one::<bool>("cpuusage").unwrap(), &config);
     let tmp: Vec<&str> = cpus.split("\n").collect();
     addLine!(
         lines,
-        if tmp.len() > 1 {
-            Some(format!("CPUs"))
+        if cpu_count > 1 {
+            Some("CPUs".to_string())
         } else {
-            Some(format!("CPU"))
+            Some("CPU".to_string())
         },
         "".to_string()
     );
@@ -232,15 +294,30 @@ fn main() {
         addLine!(lines, None, i.to_string(), 1);
     }
 
+    // Load
+    addLine!(lines, Some("Load".to_string()), get_load(&sys));
+
+    // CPU vulnerabilities, opt-in through the config
+    if let Some(vulns) = get_vulnerabilities(config.vulnerabilities) {
+        if vulns.contains('\n') {
+            addLine!(lines, Some("Vulns".to_string()), "".to_string());
+            for i in vulns.split("\n") {
+                addLine!(lines, None, i.to_string(), 1);
+            }
+        } else {
+            addLine!(lines, Some("Vulns".to_string()), vulns);
+        }
+    }
+
     // GPUs
-    let gpus = get_gpus();
+    let (gpus, gpu_count) = get_gpus(&gpu_list, &drm_cards, &config);
     let tmp: Vec<&str> = gpus.split("\n").collect();
     addLine!(
         lines,
-        if tmp.len() > 1 {
-            Some(format!("GPUs"))
+        if gpu_count > 1 {
+            Some("GPUs".to_string())
         } else {
-            Some(format!("GPU"))
+            Some("GPU".to_string())
         },
         "".to_string()
     );
@@ -252,14 +329,50 @@ fn main() {
     addLine!(
         lines,
         Some("RAM".to_string()),
-        format!("{:.2}/{:.2}GiB ({:.2}%)", sys.used_memory() as f32/1073741824.0, sys.total_memory() as f32/1073741824.0, sys.used_memory() as f32/sys.total_memory() as f32 * 100.0)
+        format!("{} ({:.2}%)", config.units.bytes_pair(sys.used_memory(), sys.total_memory()), sys.used_memory() as f32/sys.total_memory() as f32 * 100.0)
     );
+    if config.memory_expanded {
+        for i in get_memory_details(&config.units).unwrap_or_default().lines() {
+            addLine!(lines, None, i.to_string(), 1);
+        }
+    }
+    if config.memory_dimms {
+        if let Some(dimms) = get_dimms(config.memory_dmi_table.as_ref(), &config.units) {
+            let mut tmp = dimms.lines();
+            addLine!(lines, Some("DIMMs".to_string()), tmp.next().unwrap_or_default().to_string());
+            for i in tmp {
+                addLine!(lines, None, i.to_string(), 1);
+            }
+        }
+    }
     if sys.total_swap() > 0 {
        addLine!(
            lines,
            Some("SWAP".to_string()),
-           format!("{:.2}/{:.2}GiB ({:.2}%)", sys.used_swap() as f32/1073741824.0, sys.total_swap() as f32/1073741824.0, sys.used_swap() as f32/sys.total_swap() as f32 * 100.0)
+           format!("{} ({:.2}%)", config.units.bytes_pair(sys.used_swap(), sys.total_swap()), sys.used_swap() as f32/sys.total_swap() as f32 * 100.0)
        );
+       if config.memory_expanded {
+           for i in get_swap_details(&config.units).unwrap_or_default().lines() {
+               addLine!(lines, None, i.to_string(), 1);
+           }
+       }
+    }
+
+    // Disks
+    if let Some(disks) = get_disks(config.disk_mounts.as_deref(), &config.units) {
+        let tmp: Vec<&str> = disks.split("\n").collect();
+        addLine!(
+            lines,
+            if tmp.len() > 1 {
+                Some("Disks".to_string())
+            } else {
+                Some("Disk".to_string())
+            },
+            "".to_string()
+        );
+        for i in tmp {
+            addLine!(lines, None, i.to_string(), 1);
+        }
     }
 
     // Image
@@ -323,7 +436,7 @@ fn main() {
 
         if strs.len() == 1 {
             to_print.push(format!("{}{}", " ".repeat(line.newline_left_pad), tmp));
-        } else if strs.len() > 1 {
+        } else if !strs.is_empty() {
             let strs_enumerated = strs.iter().clone().enumerate();
             let cnt = strs_enumerated.len();
             for (i, splt) in strs_enumerated {
@@ -341,18 +454,18 @@ fn main() {
 
     // Color block calc
     let colorblockwidth_opt = args.get_one::<u16>("colorwidth");
-    let colorblockwidth = if colorblockwidth_opt.is_some() {
-        colorblockwidth_opt.unwrap().clone()
+    let colorblockwidth = if let Some(width) = colorblockwidth_opt {
+        *width
     } else {
         3
     } as u32;
     let colorblock_str = " ".repeat(colorblockwidth as usize);
-    let has_truecolor = args.get_one::<bool>("truecolor").unwrap().clone();
+    let has_truecolor = *args.get_one::<bool>("truecolor").unwrap();
 
     // text out
     moveCursor!(0, 0);
     for p in to_print.clone().iter() {
-        moveCursorX!((im_w + (1 * ((im_w != 0) as u32))) as u16);
+        moveCursorX!((im_w + ((im_w != 0) as u32)) as u16);
         println!("{}", p)
     }
 
@@ -371,7 +484,7 @@ fn main() {
 
                 line += "▄".on_truecolor(bg.r, bg.g, bg.b).truecolor(fg.r, fg.g, fg.b).to_string().as_str();
             }
-            moveCursorX!((im_w + (1 * ((im_w != 0) as u32))) as u16);
+            moveCursorX!((im_w + ((im_w != 0) as u32)) as u16);
             println!("{}", line);
         }
     } else { // Fill in space not taken by truecolor code
@@ -383,9 +496,9 @@ fn main() {
     // Standard colorblock out
     if colorblockwidth > 0 {
         println!();
-        moveCursorX!((im_w + (1 * ((im_w != 0) as u32))) as u16);
+        moveCursorX!((im_w + ((im_w != 0) as u32)) as u16);
         println!("{}{}{}{}{}{}{}{}", colorblock_str.on_black(), colorblock_str.on_red(), colorblock_str.on_green(), colorblock_str.on_yellow(), colorblock_str.on_blue(), colorblock_str.on_purple(), colorblock_str.on_cyan(), colorblock_str.on_white());
-        moveCursorX!((im_w + (1 * ((im_w != 0) as u32))) as u16);
+        moveCursorX!((im_w + ((im_w != 0) as u32)) as u16);
         println!("{}{}{}{}{}{}{}{}", colorblock_str.on_bright_black(), colorblock_str.on_bright_red(), colorblock_str.on_bright_green(), colorblock_str.on_bright_yellow(), colorblock_str.on_bright_blue(), colorblock_str.on_bright_purple(), colorblock_str.on_bright_cyan(), colorblock_str.on_bright_white());
     }
     // Newline (duhhhh)
@@ -0,0 +1,264 @@
+// Expanded memory breakdown from /proc/meminfo, plus compressed swap (zram, zswap) and each swap device.
+// sysinfo only has used/total, which says nothing about how much of "used" is reclaimable cache
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{units::Units, utils::format_tree};
+
+/// /proc/meminfo as bytes, huge page counts are left as plain counts
+pub fn parse_meminfo(text: &str) -> HashMap<String, u64> {
+    let mut values: HashMap<String, u64> = HashMap::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = value.split_whitespace();
+        let Some(Ok(number)) = parts.next().map(|n| n.parse::<u64>()) else {
+            continue;
+        };
+        let number = match parts.next() {
+            Some("kB") => number * 1024,
+            _ => number,
+        };
+        values.insert(key.trim().to_string(), number);
+    }
+    values
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SwapDevice {
+    pub name: String,
+    // `partition` or `file`
+    pub kind: String,
+    pub size: u64,
+    pub used: u64,
+    pub priority: i32,
+}
+
+/// /proc/swaps, sizes there are in KiB
+pub fn parse_swaps(text: &str) -> Vec<SwapDevice> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [name, kind, size, used, priority] = parts.as_slice() else {
+                return None;
+            };
+            Some(SwapDevice {
+                // Spaces in paths are escaped as octal
+                name: name.replace("\\040", " "),
+                kind: kind.to_string(),
+                size: size.parse::<u64>().ok()? * 1024,
+                used: used.parse::<u64>().ok()? * 1024,
+                priority: priority.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// zram `mm_stat` as (original size, compressed size, memory actually used), all in bytes
+pub fn parse_mm_stat(text: &str) -> Option<(u64, u64, u64)> {
+    let stats: Vec<u64> = text.split_whitespace().filter_map(|n| n.parse().ok()).collect();
+    Some((*stats.first()?, *stats.get(1)?, *stats.get(2)?))
+}
+
+/// The active algorithm is the bracketed one in `comp_algorithm`
+pub fn parse_comp_algorithm(text: &str) -> Option<String> {
+    let start = text.find('[')?;
+    let end = text.find(']')?;
+    Some(text[start + 1..end].to_string())
+}
+
+/// Configured zram devices from /sys/block, whether or not they are used as swap. Unconfigured ones have a zero
+/// `disksize`
+fn get_zram_devices() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/sys/block") else {
+        return vec![];
+    };
+    let mut devices: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("zram"))
+        .filter(|name| {
+            let disksize = fs::read_to_string(Path::new("/sys/block").join(name).join("disksize"));
+            disksize.is_ok_and(|size| size.trim() != "0")
+        })
+        .collect();
+    devices.sort();
+    devices
+}
+
+fn describe_zram(name: &str) -> Option<String> {
+    let device = Path::new("/sys/block").join(name.trim_start_matches("/dev/"));
+    let (original, compressed, _) = parse_mm_stat(&fs::read_to_string(device.join("mm_stat")).ok()?)?;
+    let algorithm = fs::read_to_string(device.join("comp_algorithm")).ok().and_then(|a| parse_comp_algorithm(&a));
+
+    let mut parts = vec![match algorithm {
+        Some(algorithm) => format!("zram, {}", algorithm),
+        None => "zram".to_string(),
+    }];
+    if compressed > 0 {
+        parts.push(format!("{:.1}x ratio", original as f64 / compressed as f64));
+    }
+    Some(parts.join(", "))
+}
+
+// zram that isn't swap is usually a compressed /tmp or cache filesystem, it still takes RAM so it's listed here
+fn describe_zram_disk(name: &str, units: &Units) -> Option<String> {
+    let device = Path::new("/sys/block").join(name);
+    let (original, _, used) = parse_mm_stat(&fs::read_to_string(device.join("mm_stat")).ok()?)?;
+    let disksize: u64 = fs::read_to_string(device.join("disksize")).ok()?.trim().parse().ok()?;
+
+    let path = format!("/dev/{}", name);
+    let mount_point = fs::read_to_string("/proc/mounts").ok().and_then(|mounts| {
+        mounts.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next() != Some(path.as_str()) {
+                return None;
+            }
+            parts.next().map(|m| m.to_string())
+        })
+    });
+
+    let mut out = format!(
+        "{} ({}): {} stored in {}",
+        path,
+        describe_zram(name).unwrap_or_else(|| "zram".to_string()),
+        units.bytes_pair(original, disksize),
+        units.bytes(used)
+    );
+    if let Some(mount_point) = mount_point {
+        out += format!(", mounted at {}", mount_point).as_str();
+    }
+    Some(out)
+}
+
+// Newer kernels put zswap in meminfo, older ones only have it in debugfs which needs root
+fn describe_zswap(meminfo: &HashMap<String, u64>, units: &Units) -> Option<String> {
+    let enabled = fs::read_to_string("/sys/module/zswap/parameters/enabled").ok()?;
+    if enabled.trim() != "Y" {
+        return None;
+    }
+
+    let (pool, stored) = match (meminfo.get("Zswap"), meminfo.get("Zswapped")) {
+        (Some(pool), Some(stored)) => (*pool, *stored),
+        _ => {
+            let read = |file: &str| -> Option<u64> {
+                fs::read_to_string(Path::new("/sys/kernel/debug/zswap").join(file)).ok()?.trim().parse().ok()
+            };
+            // stored_pages is in pages, 4KiB everywhere zswap is used in practice
+            (read("pool_total_size")?, read("stored_pages")? * 4096)
+        }
+    };
+
+    if pool == 0 {
+        return Some("zswap enabled, empty".to_string());
+    }
+    Some(format!("zswap {} in {} ({:.1}x)", units.bytes(stored), units.bytes(pool), stored as f64 / pool as f64))
+}
+
+/// Sub-lines for the RAM entry: available/cache/buffers/shared, huge pages, zswap and zram devices that aren't
+/// used as swap
+pub fn get_memory_details(units: &Units) -> Option<String> {
+    let meminfo = parse_meminfo(&fs::read_to_string("/proc/meminfo").ok()?);
+    let get = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+
+    let mut items: Vec<String> = vec![format!(
+        "{} available, {} cache, {} buffers, {} shared",
+        units.bytes(get("MemAvailable")),
+        units.bytes(get("Cached") + get("SReclaimable")),
+        units.bytes(get("Buffers")),
+        units.bytes(get("Shmem"))
+    )];
+
+    if get("HugePages_Total") > 0 {
+        items.push(format!(
+            "Huge pages {}/{} used, {} each",
+            get("HugePages_Total") - get("HugePages_Free"),
+            get("HugePages_Total"),
+            units.bytes(get("Hugepagesize"))
+        ));
+    }
+    if get("AnonHugePages") > 0 {
+        items.push(format!("Transparent huge pages {}", units.bytes(get("AnonHugePages"))));
+    }
+    if let Some(zswap) = describe_zswap(&meminfo, units) {
+        items.push(zswap);
+    }
+
+    // zram swap devices already show up under Swap
+    let swaps = parse_swaps(&fs::read_to_string("/proc/swaps").unwrap_or_default());
+    for name in get_zram_devices() {
+        if swaps.iter().any(|swap| swap.name == format!("/dev/{}", name)) {
+            continue;
+        }
+        if let Some(zram) = describe_zram_disk(&name, units) {
+            items.push(zram);
+        }
+    }
+
+    Some(format_tree(&items))
+}
+
+/// One sub-line per swap device, None without any swap
+pub fn get_swap_details(units: &Units) -> Option<String> {
+    let swaps = parse_swaps(&fs::read_to_string("/proc/swaps").ok()?);
+    if swaps.is_empty() {
+        return None;
+    }
+
+    let items: Vec<String> = swaps
+        .iter()
+        .map(|swap| {
+            let kind = if swap.name.starts_with("/dev/zram") {
+                describe_zram(&swap.name).unwrap_or_else(|| "zram".to_string())
+            } else {
+                swap.kind.clone()
+            };
+            format!(
+                "{} ({}): {}, priority {}",
+                swap.name,
+                kind,
+                units.bytes_pair(swap.used, swap.size),
+                swap.priority
+            )
+        })
+        .collect();
+
+    Some(format_tree(&items))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meminfo() {
+        let values = parse_meminfo("MemTotal:       16281024 kB\nHugePages_Total:       4\nbogus\n");
+        assert_eq!(values.get("MemTotal"), Some(&(16281024 * 1024)));
+        assert_eq!(values.get("HugePages_Total"), Some(&4));
+    }
+
+    #[test]
+    fn swaps() {
+        let swaps = parse_swaps(
+            "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n\
+             /dev/zram0                              partition\t8388604\t\t1024\t\t100\n\
+             /swap\\040file                           file\t\t4194300\t\t0\t\t-2\n",
+        );
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(swaps[0].size, 8388604 * 1024);
+        assert_eq!(swaps[1].name, "/swap file");
+        assert_eq!(swaps[1].priority, -2);
+    }
+
+    #[test]
+    fn zram_stats() {
+        assert_eq!(
+            parse_mm_stat("  1048576   262144   294912        0   294912        0        0        0        0\n"),
+            Some((1048576, 262144, 294912))
+        );
+        assert_eq!(parse_mm_stat("1048576\n"), None);
+        assert_eq!(parse_comp_algorithm("lzo lzo-rle lz4 lz4hc 842 [zstd]\n"), Some("zstd".to_string()));
+        assert_eq!(parse_comp_algorithm("lzo-rle\n"), None);
+    }
+}
@@ -1,5 +1,6 @@
 use std::fs;
 
+use regex::Regex;
 use which::which;
 
 //use crate::utils::count_newlines_hyperscreaming;
@@ -15,4 +16,73 @@ pub fn get_packages() -> String {
     }
 
     return packages.trim_end().to_string().trim_end_matches(",").to_string().trim_end_matches(", ").to_string();
-}
\ No newline at end of file
+}
+
+/// Looks up the installed version of a package straight from the package manager's database.
+/// Returns the upstream version with the epoch and distro release stripped
+pub fn get_package_version(name: &str) -> Option<String> {
+    let version_pattern = Regex::new(r"\d+(\.\d+)+").unwrap();
+
+    // pacman: one directory per package named `name-version-release`
+    if let Ok(entries) = fs::read_dir("/var/lib/pacman/local/") {
+        for entry in entries.flatten() {
+            let dirname = entry.file_name().to_string_lossy().to_string();
+            if let Some(rest) = dirname.strip_prefix(format!("{}-", name).as_str()) {
+                // Version and release never contain dashes, anything else is a different package like `name-extras`
+                if rest.matches('-').count() == 1 {
+                    let ver = rest.split('-').next().unwrap();
+                    let ver = ver.rsplit(':').next().unwrap();
+                    if let Some(m) = version_pattern.find(ver) {
+                        return Some(m.as_str().to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    // dpkg: one big status file with blank-line separated stanzas
+    if let Ok(status) = fs::read_to_string("/var/lib/dpkg/status") {
+        if let Some(ver) = find_dpkg_version(&status, name) {
+            if let Some(m) = version_pattern.find(ver.rsplit(':').next().unwrap()) {
+                return Some(m.as_str().to_string());
+            }
+        }
+    }
+
+    None
+}
+
+/// Raw `Version:` of `name` in a dpkg status file. Removed but not purged packages keep their stanza with a
+/// `deinstall ok config-files` status, those are skipped
+fn find_dpkg_version<'a>(status: &'a str, name: &str) -> Option<&'a str> {
+    for stanza in status.split("\n\n") {
+        if !stanza.lines().any(|l| l == format!("Package: {}", name)) {
+            continue;
+        }
+        let installed = stanza
+            .lines()
+            .find_map(|l| l.strip_prefix("Status: "))
+            .is_some_and(|s| s.split_whitespace().nth(2) == Some("installed"));
+        if !installed {
+            continue;
+        }
+        if let Some(ver) = stanza.lines().find_map(|l| l.strip_prefix("Version: ")) {
+            return Some(ver);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dpkg_status() {
+        let status = "Package: gnome-shell\nStatus: deinstall ok config-files\nVersion: 43.9-0+deb12u2\n\n\
+                      Package: xfce4-session\nStatus: install ok installed\nVersion: 4.18.1-1\n";
+        assert_eq!(find_dpkg_version(status, "gnome-shell"), None);
+        assert_eq!(find_dpkg_version(status, "xfce4-session"), Some("4.18.1-1"));
+        assert_eq!(find_dpkg_version(status, "mate-session-manager"), None);
+    }
+}
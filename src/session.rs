@@ -0,0 +1,114 @@
+use std::env;
+use sysinfo::{Pid, System};
+
+/// Process names of the daemons that own a remote login, newer OpenSSH splits the per-session
+/// process out into `sshd-session`
+pub const REMOTE_DAEMONS: [&str; 3] = ["sshd", "sshd-session", "mosh-server"];
+
+/// Walk up the process tree from ourselves and return the name of the first ancestor matching one of `names`
+pub fn find_ancestor(sys: &System, names: &[&str]) -> Option<String> {
+    let mut pid = Some(Pid::from_u32(std::process::id()));
+
+    while let Some(current) = pid {
+        let proc = sys.process(current)?;
+        let name = proc.name().to_string_lossy().to_lowercase();
+        if names.contains(&name.as_str()) {
+            return Some(name);
+        }
+        pid = proc.parent();
+    }
+
+    None
+}
+
+/// Formats an address/port pair, wrapping IPv6 addresses in brackets so the port stays readable
+fn format_addr(addr: &str, port: &str) -> String {
+    if addr.contains(':') {
+        format!("[{}]:{}", addr, port)
+    } else {
+        format!("{}:{}", addr, port)
+    }
+}
+
+/// Client address from `SSH_CONNECTION` ("client_ip client_port server_ip server_port") or the older
+/// `SSH_CLIENT` ("client_ip client_port server_port")
+fn parse_ssh_client(conn: &str) -> Option<String> {
+    let mut parts = conn.split_whitespace();
+    let addr = parts.next()?;
+    let port = parts.next()?;
+
+    Some(format_addr(addr, port))
+}
+
+/// Client address of the current ssh connection
+fn get_ssh_client() -> Option<String> {
+    let conn = env::var("SSH_CONNECTION")
+        .or_else(|_| env::var("SSH_CLIENT"))
+        .ok()?;
+    parse_ssh_client(&conn)
+}
+
+/// Whether we're running under ssh or mosh, anything the display server says then describes the client
+pub fn is_remote(sys: &System) -> bool {
+    get_ssh_client().is_some() || env::var("SSH_TTY").is_ok() || find_ancestor(sys, &REMOTE_DAEMONS).is_some()
+}
+
+/// Returns a description of how this session is connected, or None for a plain local graphical session
+pub fn get_session(sys: &System) -> Option<String> {
+    let ssh_client = get_ssh_client();
+    let ssh_tty = env::var("SSH_TTY").ok();
+
+    if find_ancestor(sys, &["mosh-server"]).is_some() {
+        // mosh-server detaches from the ssh session that started it, so the client is usually unknown
+        return Some(match ssh_client {
+            Some(client) => format!("Mosh from {}", client),
+            None => "Mosh".to_string()
+        });
+    }
+
+    if ssh_client.is_some() || ssh_tty.is_some() {
+        let mut out = match ssh_client {
+            Some(client) => format!("SSH from {}", client),
+            None => "SSH".to_string()
+        };
+        if let Some(tty) = ssh_tty {
+            out += format!(" ({})", tty.trim_start_matches("/dev/")).as_str();
+        }
+        return Some(out);
+    }
+
+    if find_ancestor(sys, &REMOTE_DAEMONS).is_some() {
+        // Environment was scrubbed (sudo, su -, etc.) but we are still under sshd
+        return Some("SSH".to_string());
+    }
+
+    if env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "tty") {
+        return Some(match env::var("XDG_VTNR") {
+            Ok(vt) => format!("Local TTY (tty{})", vt),
+            Err(_) => "Local TTY".to_string()
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssh_clients() {
+        assert_eq!(parse_ssh_client("192.168.1.20 51234 192.168.1.2 22").as_deref(), Some("192.168.1.20:51234"));
+        assert_eq!(parse_ssh_client("2001:db8::20 51234 2001:db8::2 22").as_deref(), Some("[2001:db8::20]:51234"));
+        // SSH_CLIENT has one field less
+        assert_eq!(parse_ssh_client("10.0.0.5 40022 22").as_deref(), Some("10.0.0.5:40022"));
+        assert_eq!(parse_ssh_client("192.168.1.20"), None);
+        assert_eq!(parse_ssh_client(""), None);
+    }
+
+    #[test]
+    fn addresses() {
+        assert_eq!(format_addr("10.0.0.5", "22"), "10.0.0.5:22");
+        assert_eq!(format_addr("fe80::1", "22"), "[fe80::1]:22");
+    }
+}
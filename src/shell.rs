@@ -17,8 +17,8 @@ pub fn get_shell(sys: &System) -> String {
             // update: found out this is actually fairly fast, it was sysinfo's threading that slowed it
             let tmp = Command::new("zsh").arg("--version").output();
             let vermatch = Regex::new(r"(?i)zsh [\d\.]+").unwrap();
-            if tmp.is_ok() {
-                format!("{}", vermatch.find(String::from_utf8(tmp.unwrap().stdout.to_vec()).unwrap().as_str()).unwrap().as_str())
+            if let Ok(tmp) = tmp {
+                vermatch.find(String::from_utf8(tmp.stdout.to_vec()).unwrap().as_str()).unwrap().as_str().to_string()
             } else {
                 "zsh ?.?".to_string()
             }
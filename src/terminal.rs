@@ -1,7 +1,9 @@
-use std::process::Command;
+use std::{env, process::Command};
 use sysinfo::{Pid, System};
 use regex::{self, Regex};
 
+use crate::session::REMOTE_DAEMONS;
+
 pub fn get_term(sys: &System) -> String {
     let shell_name = sys.process(
             sys.process(Pid::from_u32(std::os::unix::process::parent_id()))
@@ -14,6 +16,14 @@ pub fn get_term(sys: &System) -> String {
         .to_string()
         .to_lowercase();
 
+    // Over ssh/mosh the parent is the daemon, the actual terminal lives on the client so go off what it told us
+    if REMOTE_DAEMONS.contains(&shell_name.as_str()) {
+        let term = env::var("TERM_PROGRAM")
+            .or_else(|_| env::var("TERM"))
+            .unwrap_or_else(|_| "Unknown".to_string());
+        return format!("{} (remote)", term);
+    }
+
     match shell_name.as_str() {
         "kitty" => {
             let tmp = Command::new("kitty").arg("--version").output();
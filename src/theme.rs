@@ -0,0 +1,188 @@
+use std::{env, path::PathBuf};
+
+use crate::{
+    desktop::get_de_name,
+    utils::{read_ini_value, xdg_dir},
+};
+
+pub struct Themes {
+    pub theme: Option<String>,
+    pub icons: Option<String>,
+    pub cursor: Option<String>,
+    pub font: Option<String>,
+}
+
+// What one config file says, tagged with the toolkit it applies to
+struct ThemeSource {
+    tag: &'static str,
+    theme: Option<String>,
+    icons: Option<String>,
+    cursor: Option<String>,
+    font: Option<String>,
+}
+
+fn home_dir() -> PathBuf {
+    PathBuf::from(env::var("HOME").unwrap_or_default())
+}
+
+fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config").unwrap_or_else(|| home_dir().join(".config"))
+}
+
+// Qt stores fonts as a QFont string, `Noto Sans,10,-1,5,50,0,0,0,0,0`, we only want family and size
+fn format_qt_font(font: String) -> Option<String> {
+    // Old qt5ct versions write an opaque `@Variant(...)` blob
+    if font.starts_with('@') {
+        return None;
+    }
+    let mut parts = font.split(',');
+    let family = parts.next()?.trim();
+    match parts.next() {
+        Some(size) => Some(format!("{} {}", family, size.trim())),
+        None => Some(family.to_string()),
+    }
+}
+
+fn read_gtk2() -> ThemeSource {
+    let path = home_dir().join(".gtkrc-2.0");
+    ThemeSource {
+        tag: "GTK2",
+        theme: read_ini_value(&path, "", "gtk-theme-name"),
+        icons: read_ini_value(&path, "", "gtk-icon-theme-name"),
+        cursor: read_ini_value(&path, "", "gtk-cursor-theme-name"),
+        font: read_ini_value(&path, "", "gtk-font-name"),
+    }
+}
+
+fn read_gtk_settings(tag: &'static str, dir: &str) -> ThemeSource {
+    let path = config_dir().join(dir).join("settings.ini");
+    ThemeSource {
+        tag,
+        theme: read_ini_value(&path, "Settings", "gtk-theme-name"),
+        icons: read_ini_value(&path, "Settings", "gtk-icon-theme-name"),
+        cursor: read_ini_value(&path, "Settings", "gtk-cursor-theme-name"),
+        font: read_ini_value(&path, "Settings", "gtk-font-name"),
+    }
+}
+
+fn read_kde() -> ThemeSource {
+    let globals = config_dir().join("kdeglobals");
+    ThemeSource {
+        tag: "KDE",
+        // Plasma 6 moved the widget style from [General] to [KDE]
+        theme: read_ini_value(&globals, "KDE", "widgetStyle")
+            .or_else(|| read_ini_value(&globals, "General", "widgetStyle")),
+        icons: read_ini_value(&globals, "Icons", "Theme"),
+        cursor: read_ini_value(&config_dir().join("kcminputrc"), "Mouse", "cursorTheme"),
+        font: read_ini_value(&globals, "General", "font").and_then(format_qt_font),
+    }
+}
+
+fn read_qtct(tag: &'static str, name: &str) -> ThemeSource {
+    let path = config_dir().join(name).join(format!("{}.conf", name));
+    ThemeSource {
+        tag,
+        theme: read_ini_value(&path, "Appearance", "style"),
+        icons: read_ini_value(&path, "Appearance", "icon_theme"),
+        cursor: None,
+        font: read_ini_value(&path, "Fonts", "general").and_then(format_qt_font),
+    }
+}
+
+// Merges the values of every source into `Adwaita [GTK3/4], Breeze [KDE]`, sources agreeing on a value share one entry
+fn combine(values: Vec<(&'static str, Option<String>)>) -> Option<String> {
+    let mut merged: Vec<(String, Vec<&'static str>)> = vec![];
+    for (tag, value) in values {
+        let Some(value) = value else {
+            continue;
+        };
+        if let Some(existing) = merged.iter_mut().find(|(v, _)| *v == value) {
+            existing.1.push(tag);
+        } else {
+            merged.push((value, vec![tag]));
+        }
+    }
+
+    if merged.is_empty() {
+        return None;
+    }
+
+    let out: Vec<String> = merged
+        .into_iter()
+        .map(|(value, tags)| {
+            // GTK3, GTK2 -> GTK3/2, only when the previous source is the same toolkit
+            let toolkit = |t: &'static str| t.trim_end_matches(|c: char| c.is_ascii_digit());
+            let mut tag = tags[0].to_string();
+            for pair in tags.windows(2) {
+                let (previous, t) = (pair[0], pair[1]);
+                if toolkit(t) != t && toolkit(t) == toolkit(previous) {
+                    tag += format!("/{}", &t[toolkit(t).len()..]).as_str();
+                } else {
+                    tag += format!("/{}", t).as_str();
+                }
+            }
+            format!("{} [{}]", value, tag)
+        })
+        .collect();
+    Some(out.join(", "))
+}
+
+pub fn get_themes() -> Themes {
+    let de_name = get_de_name().unwrap_or_default();
+
+    let gtk = vec![read_gtk_settings("GTK3", "gtk-3.0"), read_gtk_settings("GTK4", "gtk-4.0"), read_gtk2()];
+    let qt = vec![read_kde(), read_qtct("Qt6", "qt6ct"), read_qtct("Qt5", "qt5ct")];
+
+    // Qt desktops list their own settings first, everything else is assumed to be GTK based
+    let sources: Vec<ThemeSource> = match de_name.as_str() {
+        "Kde" | "Lxqt" => qt.into_iter().chain(gtk).collect(),
+        _ => gtk.into_iter().chain(qt).collect(),
+    };
+
+    // Cursors are global so only report the one actually in use: the env var wins, then the DE's own
+    // setting, then the X default from ~/.icons
+    let cursor = env::var("XCURSOR_THEME")
+        .ok()
+        .filter(|c| !c.is_empty())
+        .or_else(|| sources.iter().find_map(|s| s.cursor.clone()))
+        .or_else(|| {
+            read_ini_value(&home_dir().join(".icons/default/index.theme"), "Icon Theme", "Inherits")
+        });
+
+    Themes {
+        theme: combine(sources.iter().map(|s| (s.tag, s.theme.clone())).collect()),
+        icons: combine(sources.iter().map(|s| (s.tag, s.icons.clone())).collect()),
+        cursor,
+        font: combine(sources.iter().map(|s| (s.tag, s.font.clone())).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combined_tags() {
+        let value = || Some("Breeze".to_string());
+        assert_eq!(
+            combine(vec![("GTK3", value()), ("GTK4", value()), ("GTK2", value())]).as_deref(),
+            Some("Breeze [GTK3/4/2]")
+        );
+        assert_eq!(
+            combine(vec![("GTK3", value()), ("KDE", value()), ("GTK2", value())]).as_deref(),
+            Some("Breeze [GTK3/KDE/GTK2]")
+        );
+        assert_eq!(
+            combine(vec![("KDE", value()), ("Qt6", None), ("GTK3", Some("Adwaita".to_string()))]).as_deref(),
+            Some("Breeze [KDE], Adwaita [GTK3]")
+        );
+        assert_eq!(combine(vec![("GTK3", None)]), None);
+    }
+
+    #[test]
+    fn qt_fonts() {
+        assert_eq!(format_qt_font("Noto Sans,10,-1,5,50,0,0,0,0,0".to_string()).as_deref(), Some("Noto Sans 10"));
+        assert_eq!(format_qt_font("Inter".to_string()).as_deref(), Some("Inter"));
+        assert_eq!(format_qt_font("@Variant(\\0\\0\\0@)".to_string()), None);
+    }
+}
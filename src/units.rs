@@ -0,0 +1,145 @@
+// Shared formatting for sizes, frequencies, temperatures and durations, so every section scales, rounds and
+// labels its numbers the same way. Configured from `[units]`
+use crate::conf::{ByteUnits, TemperatureUnit};
+
+const IEC: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+const SI: [&str; 6] = ["B", "kB", "MB", "GB", "TB", "PB"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Units {
+    pub bytes: ByteUnits,
+    // Decimal places for scaled sizes and frequencies, temperatures are always whole degrees
+    pub precision: usize,
+    pub temperature: TemperatureUnit,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Units { bytes: ByteUnits::Iec, precision: 1, temperature: TemperatureUnit::Celsius }
+    }
+}
+
+impl Units {
+    // The largest unit `bytes` fills at least once, as (divisor, suffix)
+    fn byte_scale(&self, bytes: u64) -> (f64, &'static str) {
+        let (step, names) = match self.bytes {
+            ByteUnits::Iec => (1024.0, IEC),
+            ByteUnits::Si => (1000.0, SI),
+        };
+        let mut divisor = 1.0;
+        let mut name = names[0];
+        for next in &names[1..] {
+            if (bytes as f64) < divisor * step {
+                break;
+            }
+            divisor *= step;
+            name = next;
+        }
+        (divisor, name)
+    }
+
+    // Plain bytes never get decimals
+    fn scaled(&self, value: f64, divisor: f64) -> String {
+        let precision = if divisor == 1.0 {
+            0
+        } else {
+            self.precision
+        };
+        format!("{:.*}", precision, value / divisor)
+    }
+
+    /// `15.5GiB`
+    pub fn bytes(&self, bytes: u64) -> String {
+        let (divisor, name) = self.byte_scale(bytes);
+        format!("{}{}", self.scaled(bytes as f64, divisor), name)
+    }
+
+    /// `2.1/24.0GiB`, both in the unit of `total`
+    pub fn bytes_pair(&self, used: u64, total: u64) -> String {
+        let (divisor, name) = self.byte_scale(total);
+        format!("{}/{}{}", self.scaled(used as f64, divisor), self.scaled(total as f64, divisor), name)
+    }
+
+    fn frequency_scale(mhz: f64) -> (f64, &'static str) {
+        if mhz >= 1000.0 {
+            (1000.0, "GHz")
+        } else {
+            (1.0, "MHz")
+        }
+    }
+
+    /// `5.4GHz`, `800MHz`
+    pub fn frequency(&self, mhz: f64) -> String {
+        let (divisor, name) = Self::frequency_scale(mhz);
+        format!("{}{}", self.scaled(mhz, divisor), name)
+    }
+
+    /// `0.8-5.4GHz`, both in the unit of `high`
+    pub fn frequency_pair(&self, low: f64, high: f64) -> String {
+        let (divisor, name) = Self::frequency_scale(high);
+        format!("{}-{}{}", self.scaled(low, divisor), self.scaled(high, divisor), name)
+    }
+
+    /// `96/1250MHz`, current and max clock always in MHz. Scaling by the max would turn low clocks like that
+    /// into `0.1/1.2GHz`
+    pub fn clock_pair(&self, current: f64, max: f64) -> String {
+        format!("{}/{}MHz", self.scaled(current, 1.0), self.scaled(max, 1.0))
+    }
+
+    /// `43°C`, `109°F`
+    pub fn temperature(&self, celsius: f32) -> String {
+        match self.temperature {
+            TemperatureUnit::Celsius => format!("{:.0}°C", celsius),
+            TemperatureUnit::Fahrenheit => format!("{:.0}°F", celsius * 9.0 / 5.0 + 32.0),
+        }
+    }
+
+    /// `2d 3h 14m`, down to minutes, `0m` for anything shorter
+    pub fn duration(&self, seconds: u64) -> String {
+        let (centuries, seconds) = (seconds / 3_153_600_000, seconds % 3_153_600_000);
+        let (decades, seconds) = (seconds / 315_360_000, seconds % 315_360_000);
+        let (years, seconds) = (seconds / 31_536_000, seconds % 31_536_000);
+        let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+        let (hours, seconds) = (seconds / 3_600, seconds % 3_600);
+        let minutes = seconds / 60;
+
+        let fields = [(centuries, "C."), (decades, "D."), (years, "y"), (days, "d"), (hours, "h"), (minutes, "m")];
+        let parts: Vec<String> = fields
+            .iter()
+            .filter(|(value, _)| *value > 0)
+            .map(|(value, name)| format!("{}{}", value, name))
+            .collect();
+        if parts.is_empty() {
+            return "0m".to_string();
+        }
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaling() {
+        let iec = Units::default();
+        assert_eq!(iec.bytes(512), "512B");
+        assert_eq!(iec.bytes(16 * 1024 * 1024 * 1024), "16.0GiB");
+        assert_eq!(iec.bytes_pair(2254857830, 25769803776), "2.1/24.0GiB");
+        assert_eq!(iec.frequency(800.0), "800MHz");
+        assert_eq!(iec.frequency_pair(800.0, 5400.0), "0.8-5.4GHz");
+        assert_eq!(iec.clock_pair(96.0, 1250.0), "96/1250MHz");
+
+        let si = Units { bytes: ByteUnits::Si, precision: 2, temperature: TemperatureUnit::Fahrenheit };
+        assert_eq!(si.bytes(16 * 1024 * 1024 * 1024), "17.18GB");
+        assert_eq!(si.temperature(43.0), "109°F");
+    }
+
+    #[test]
+    fn durations() {
+        let units = Units::default();
+        assert_eq!(units.duration(59), "0m");
+        assert_eq!(units.duration(365 * 86_400 + 60), "1y 1m");
+        assert_eq!(units.duration(2 * 86_400 + 3 * 3_600 + 14 * 60 + 5), "2d 3h 14m");
+    }
+}
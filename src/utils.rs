@@ -1,3 +1,79 @@
+use std::{env, fs, path::{Path, PathBuf}, process::Command};
+
+use regex::Regex;
+
+/// An XDG base directory, `var` when set and non-empty, `$HOME/fallback` otherwise. None without either
+pub fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    match env::var(var) {
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => Some(PathBuf::from(env::var("HOME").ok()?).join(fallback)),
+    }
+}
+
+/// Runs `cmd arg` and pulls the first version-looking number out of stdout or stderr, some tools print
+/// their version to stderr (dwm, river) so both are checked
+pub fn get_cmd_version(cmd: &str, arg: &str) -> Option<String> {
+    let output = Command::new(cmd).arg(arg).output().ok()?;
+    let version_pattern = Regex::new(r"\d+\.\d+(\.\d+)?").unwrap();
+
+    let text = String::from_utf8_lossy(&output.stdout).to_string() + String::from_utf8_lossy(&output.stderr).as_ref();
+    version_pattern.find(text.as_str()).map(|m| m.as_str().to_string())
+}
+
+/// Joins items into the `│ `/`╰ ` tree used by the multi-line sections. Items can span several lines,
+/// continuation lines get indented under their item so trees can be nested
+pub fn format_tree(items: &[String]) -> String {
+    let mut out: Vec<String> = vec![];
+    for (i, item) in items.iter().enumerate() {
+        let last = i == items.len() - 1;
+        for (j, line) in item.lines().enumerate() {
+            let prefix = match (j == 0, last) {
+                (true, true) => "╰ ",
+                (true, false) => "│ ",
+                (false, true) => "  ",
+                (false, false) => "│ ",
+            };
+            out.push(format!("{}{}", prefix, line));
+        }
+    }
+    out.join("\n")
+}
+
+/// Reads `key` from `[section]` of an ini style file, use an empty section for keys above the first header
+/// (gtkrc-2.0 has no headers at all). Surrounding quotes are stripped from the value
+pub fn read_ini_value(path: &Path, section: &str, key: &str) -> Option<String> {
+    ini_value(&fs::read_to_string(path).ok()?, section, key)
+}
+
+/// `read_ini_value` on contents that were already read, for looking up many keys in the same file
+pub fn ini_value(contents: &str, section: &str, key: &str) -> Option<String> {
+    let mut current_section = "";
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            current_section = &line[1..line.len() - 1];
+            continue;
+        }
+        if current_section != section {
+            continue;
+        }
+        if let Some((k, v)) = line.split_once('=') {
+            if k.trim() == key {
+                let value = v.trim().trim_matches('"').trim();
+                if !value.is_empty() {
+                    return Some(value.to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
 // llogiq, veedrac, and regexident. you are all fucking insane, thank you <3
 // https://github.com/llogiq/newlinebench
 // MIT license
@@ -107,4 +183,27 @@
 //
 //        count
 //    }
-//}
\ No newline at end of file
+//}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ini_values() {
+        let path = env::temp_dir().join(format!("yatfpbnws-ini-{}", std::process::id()));
+        fs::write(
+            &path,
+            "gtk-theme-name=\"Adwaita\"\n\n[General]\n# font=commented\nfont = Noto Sans,10\nempty =\n\n[KDE]\nfont=Other\n",
+        )
+        .unwrap();
+
+        assert_eq!(read_ini_value(&path, "", "gtk-theme-name").as_deref(), Some("Adwaita"));
+        assert_eq!(read_ini_value(&path, "General", "font").as_deref(), Some("Noto Sans,10"));
+        assert_eq!(read_ini_value(&path, "KDE", "font").as_deref(), Some("Other"));
+        assert_eq!(read_ini_value(&path, "General", "empty"), None);
+        assert_eq!(read_ini_value(&path, "Missing", "font"), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
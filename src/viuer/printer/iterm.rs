@@ -34,7 +34,7 @@ fn color_extender(t: ColorType) -> ExtendedColorType {
         ColorType::Rgb32F  => ExtendedColorType::Rgb32F,
         ColorType::Rgba32F => ExtendedColorType::Rgba32F,
         _ => panic!("Unknown ColorType"),
-    }
+    };
 }
 
 impl Printer for iTermPrinter {
@@ -0,0 +1,163 @@
+use std::fs;
+
+use owo_colors::OwoColorize;
+
+use crate::{conf::VulnerabilityMode, utils::format_tree};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    NotAffected,
+    Mitigated,
+    Vulnerable,
+    // `Unknown: Dependent on hypervisor status` and friends
+    Unknown,
+}
+
+// (sysfs file, shown as), anything not listed here gets its file name with spaces
+const NAMES: [(&str, &str); 19] = [
+    ("spectre_v1", "Spectre v1"),
+    ("spectre_v2", "Spectre v2"),
+    ("meltdown", "Meltdown"),
+    ("spec_store_bypass", "SSB"),
+    ("l1tf", "L1TF"),
+    ("mds", "MDS"),
+    ("tsx_async_abort", "TAA"),
+    ("itlb_multihit", "iTLB multihit"),
+    ("srbds", "SRBDS"),
+    ("mmio_stale_data", "MMIO stale data"),
+    ("retbleed", "Retbleed"),
+    ("spec_rstack_overflow", "SRSO"),
+    ("gather_data_sampling", "GDS"),
+    ("reg_file_data_sampling", "RFDS"),
+    ("indirect_target_selection", "ITS"),
+    ("ghostwrite", "GhostWrite"),
+    ("tsa", "TSA"),
+    ("vmscape", "VMScape"),
+    ("old_microcode", "Old microcode"),
+];
+
+/// Status of one vulnerability file along with the parts of a mitigation that are still vulnerable,
+/// e.g. spectre_v2 is often `Mitigation: Enhanced IBRS; ...; BHI: Vulnerable`
+pub fn classify(text: &str) -> (Status, Option<String>) {
+    // itlb_multihit prefixes everything that depends on KVM, CPUs without VMX just say `Processor vulnerable`
+    let text = text.trim();
+    let text = text.strip_prefix("KVM: ").unwrap_or(text);
+    if text == "Processor vulnerable" {
+        (Status::Vulnerable, None)
+    } else if text.starts_with("Not affected") {
+        (Status::NotAffected, None)
+    } else if text.starts_with("Vulnerable") {
+        let detail = text.trim_start_matches("Vulnerable").trim_start_matches([':', ';', ' ']);
+        (Status::Vulnerable, Some(detail.to_string()).filter(|d| !d.is_empty()))
+    } else if text.starts_with("Mitigation") {
+        let gaps: Vec<&str> = text.split(';').map(|s| s.trim()).filter(|s| s.contains("Vulnerable")).collect();
+        (Status::Mitigated, Some(gaps.join("; ")).filter(|d| !d.is_empty()))
+    } else {
+        (Status::Unknown, Some(text.to_string()).filter(|d| !d.is_empty()))
+    }
+}
+
+fn get_statuses() -> Vec<(String, Status, Option<String>)> {
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu/vulnerabilities") else {
+        return vec![];
+    };
+    let mut files: Vec<String> = entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect();
+    // Known ones in roughly the order they were disclosed, new ones at the end
+    files.sort_by_key(|f| NAMES.iter().position(|(file, _)| file == f).unwrap_or(NAMES.len()));
+
+    files
+        .iter()
+        .filter_map(|file| {
+            let text = fs::read_to_string(format!("/sys/devices/system/cpu/vulnerabilities/{}", file)).ok()?;
+            let name = match NAMES.iter().find(|(f, _)| f == file) {
+                Some((_, name)) => name.to_string(),
+                None => file.replace('_', " "),
+            };
+            let (status, detail) = classify(&text);
+            Some((name, status, detail))
+        })
+        .collect()
+}
+
+/// `0 vulnerable, 9 mitigated` in compact mode, otherwise a tree with every affected issue.
+/// None when turned off or the kernel doesn't report any (non-x86 before 6.x, containers)
+pub fn get_vulnerabilities(mode: VulnerabilityMode) -> Option<String> {
+    if mode == VulnerabilityMode::Off {
+        return None;
+    }
+    let statuses = get_statuses();
+    if statuses.is_empty() {
+        return None;
+    }
+
+    let count = |status: Status| statuses.iter().filter(|(_, s, _)| *s == status).count();
+    let vulnerable = count(Status::Vulnerable);
+    let mitigated = count(Status::Mitigated);
+    let unknown = count(Status::Unknown);
+    let not_affected = count(Status::NotAffected);
+
+    if mode == VulnerabilityMode::Compact {
+        let vulnerable_text = format!("{} vulnerable", vulnerable);
+        let mut out = if vulnerable > 0 {
+            format!("{}", vulnerable_text.red())
+        } else {
+            format!("{}", vulnerable_text.green())
+        };
+        out += format!(", {} mitigated", mitigated).as_str();
+        if unknown > 0 {
+            out += format!(", {} unknown", unknown).as_str();
+        }
+        return Some(out);
+    }
+
+    let mut items: Vec<String> = statuses
+        .iter()
+        .filter(|(_, status, _)| *status != Status::NotAffected)
+        .map(|(name, status, detail)| {
+            let status = match status {
+                Status::Vulnerable => format!("{}", "Vulnerable".red()),
+                Status::Mitigated => format!("{}", "Mitigated".yellow()),
+                _ => "Unknown".to_string(),
+            };
+            match detail {
+                Some(detail) => format!("{}: {} ({})", name, status, detail),
+                None => format!("{}: {}", name, status),
+            }
+        })
+        .collect();
+    if not_affected > 0 {
+        items.push(format!("{}", format!("{} not affected", not_affected).green()));
+    }
+
+    // A lone item goes on the Vulns line itself, a one-entry tree would dangle off the label
+    if items.len() == 1 {
+        return items.pop();
+    }
+    Some(format_tree(&items))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses() {
+        assert_eq!(classify("Not affected\n"), (Status::NotAffected, None));
+        assert_eq!(
+            classify("Mitigation: Enhanced / Automatic IBRS; IBPB: conditional; BHI: Vulnerable\n"),
+            (Status::Mitigated, Some("BHI: Vulnerable".to_string()))
+        );
+        assert_eq!(classify("Mitigation: PTI"), (Status::Mitigated, None));
+        assert_eq!(
+            classify("Vulnerable: Clear CPU buffers attempted, no microcode"),
+            (Status::Vulnerable, Some("Clear CPU buffers attempted, no microcode".to_string()))
+        );
+        assert_eq!(classify("Vulnerable"), (Status::Vulnerable, None));
+
+        // itlb_multihit
+        assert_eq!(classify("KVM: Mitigation: VMX disabled\n"), (Status::Mitigated, None));
+        assert_eq!(classify("KVM: Mitigation: Split huge pages"), (Status::Mitigated, None));
+        assert_eq!(classify("KVM: Vulnerable"), (Status::Vulnerable, None));
+        assert_eq!(classify("Processor vulnerable\n"), (Status::Vulnerable, None));
+    }
+}
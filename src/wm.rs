@@ -0,0 +1,94 @@
+use std::env;
+use sysinfo::{Pid, System};
+
+use crate::{desktop::get_display_server, utils::get_cmd_version};
+
+struct WmInfo {
+    name: &'static str,
+    // Env var the compositor exports to its children, more reliable than the process table when present
+    env_marker: Option<&'static str>,
+    // Lowercase process names, keep in mind linux truncates these to 15 chars
+    procs: &'static [&'static str],
+    // Binary and argument that print a version, an empty binary runs whichever of `procs` was matched
+    version_cmd: Option<(&'static str, &'static str)>,
+}
+
+// Order matters, the first match wins. Compositors with env markers go first, then dedicated WMs,
+// then the ones that are part of a full DE
+const WMS: &[WmInfo] = &[
+    WmInfo { name: "Hyprland", env_marker: Some("HYPRLAND_INSTANCE_SIGNATURE"), procs: &["hyprland"], version_cmd: Some(("hyprctl", "version")) },
+    WmInfo { name: "Sway", env_marker: Some("SWAYSOCK"), procs: &["sway"], version_cmd: Some(("sway", "--version")) },
+    WmInfo { name: "niri", env_marker: Some("NIRI_SOCKET"), procs: &["niri"], version_cmd: Some(("niri", "--version")) },
+    WmInfo { name: "i3", env_marker: Some("I3SOCK"), procs: &["i3"], version_cmd: Some(("i3", "--version")) },
+    WmInfo { name: "river", env_marker: None, procs: &["river"], version_cmd: Some(("river", "-version")) },
+    WmInfo { name: "Wayfire", env_marker: None, procs: &["wayfire"], version_cmd: Some(("wayfire", "--version")) },
+    WmInfo { name: "labwc", env_marker: None, procs: &["labwc"], version_cmd: Some(("labwc", "--version")) },
+    WmInfo { name: "Weston", env_marker: None, procs: &["weston"], version_cmd: Some(("weston", "--version")) },
+    WmInfo { name: "bspwm", env_marker: None, procs: &["bspwm"], version_cmd: Some(("bspwm", "-v")) },
+    WmInfo { name: "awesome", env_marker: None, procs: &["awesome"], version_cmd: Some(("awesome", "--version")) },
+    WmInfo { name: "Openbox", env_marker: None, procs: &["openbox"], version_cmd: Some(("openbox", "--version")) },
+    WmInfo { name: "herbstluftwm", env_marker: None, procs: &["herbstluftwm"], version_cmd: Some(("herbstluftwm", "--version")) },
+    WmInfo { name: "Fluxbox", env_marker: None, procs: &["fluxbox"], version_cmd: Some(("fluxbox", "-version")) },
+    WmInfo { name: "IceWM", env_marker: None, procs: &["icewm"], version_cmd: Some(("icewm", "--version")) },
+    WmInfo { name: "dwm", env_marker: None, procs: &["dwm"], version_cmd: Some(("dwm", "-v")) },
+    WmInfo { name: "KWin", env_marker: None, procs: &["kwin_wayland", "kwin_x11"], version_cmd: Some(("", "--version")) },
+    WmInfo { name: "Mutter", env_marker: None, procs: &["gnome-shell"], version_cmd: Some(("gnome-shell", "--version")) },
+    WmInfo { name: "Muffin", env_marker: None, procs: &["cinnamon"], version_cmd: Some(("cinnamon", "--version")) },
+    WmInfo { name: "Xfwm4", env_marker: None, procs: &["xfwm4"], version_cmd: Some(("xfwm4", "--version")) },
+    WmInfo { name: "Marco", env_marker: None, procs: &["marco"], version_cmd: Some(("marco", "--version")) },
+    WmInfo { name: "Enlightenment", env_marker: None, procs: &["enlightenment"], version_cmd: Some(("enlightenment", "-version")) },
+];
+
+/// Finds the running window manager or compositor, returns None when there is nothing we recognize
+pub fn get_wm(sys: &System) -> Option<String> {
+    // Other users' sessions show up too on shared hosts and over ssh, only our own processes count
+    let uid = sys.process(Pid::from_u32(std::process::id())).and_then(|p| p.user_id()).cloned();
+    let running: Vec<String> = sys
+        .processes()
+        .values()
+        .filter(|p| uid.is_none() || p.user_id() == uid.as_ref())
+        .map(|p| p.name().to_string_lossy().to_lowercase())
+        .collect();
+
+    let mut found: Option<(&WmInfo, &str)> = None;
+    for wm in WMS.iter() {
+        if wm.env_marker.is_some_and(|marker| env::var(marker).is_ok()) {
+            found = Some((wm, wm.procs[0]));
+            break;
+        }
+    }
+    if found.is_none() {
+        'outer: for wm in WMS.iter() {
+            for proc in wm.procs.iter() {
+                if running.iter().any(|r| r == proc) {
+                    found = Some((wm, proc));
+                    break 'outer;
+                }
+            }
+        }
+    }
+
+    let (wm, proc) = found?;
+    let mut out = wm.name.to_string();
+
+    if let Some((cmd, arg)) = wm.version_cmd {
+        let cmd = if cmd.is_empty() {
+            proc
+        } else {
+            cmd
+        };
+        if let Some(ver) = get_cmd_version(cmd, arg) {
+            out += format!(" {}", ver).as_str();
+        }
+    }
+
+    // KWin ships one binary per display server, say which one is actually in charge
+    let server = match proc {
+        "kwin_wayland" => "Wayland",
+        "kwin_x11" => "X11",
+        _ => get_display_server(),
+    };
+    out += format!(" ({})", server).as_str();
+
+    Some(out)
+}